@@ -1,40 +1,88 @@
-use std::io::Cursor;
+use std::{sync::Arc, time::Duration};
 
-use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use rodio::{source::SineWave, OutputStream, OutputStreamHandle, Sink, Source};
 
-pub struct Beeper {
-    _stream: OutputStream,
-    stream_handle: OutputStreamHandle,
+use crate::sound::{Sound, SoundEvent, SoundTheme};
 
-    // Store two sounds now
-    beep: Vec<u8>,
+// Audio output shared by whole application.
+// Without output device beeper is silent, default beeper has no output.
+#[derive(Default)]
+pub struct Beeper {
+    output: Option<(OutputStream, OutputStreamHandle)>,
+    theme: Arc<SoundTheme>,
+    volume: f32,
 }
 
 impl Beeper {
-    pub fn new() -> Self {
-        let (_stream, stream_handle) = OutputStream::try_default().unwrap();
-
-        let beep = include_bytes!("../sounds/clack.mp3").to_vec();
+    pub fn new(theme: Arc<SoundTheme>, volume: f32) -> Self {
+        let output = match OutputStream::try_default() {
+            Ok(output) => Some(output),
+            Err(err) => {
+                println!("audio output not available, sounds disabled: {:?}", err);
+                None
+            }
+        };
 
         Self {
-            _stream,
-            stream_handle,
-            beep,
+            output,
+            theme,
+            volume,
         }
     }
 
-    /// Helper to play raw data
-    fn play(&self, data: &[u8]) {
-        if let Ok(sink) = Sink::try_new(&self.stream_handle) {
-            let cursor = Cursor::new(data.to_vec()); // Clone the data for playback
-            if let Ok(source) = Decoder::new(cursor) {
-                sink.append(source);
-                sink.detach();
+    pub fn set_theme(&mut self, theme: Arc<SoundTheme>) {
+        self.theme = theme;
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume;
+    }
+
+    pub fn play(&self, event: SoundEvent) {
+        let Some((_, stream_handle)) = &self.output else {
+            return;
+        };
+        if self.volume <= 0.0 {
+            return;
+        }
+        let sound = self.theme.sound(event);
+        if sound == Sound::None {
+            return;
+        }
+        if let Ok(sink) = Sink::try_new(stream_handle) {
+            sink.set_volume(self.volume);
+            match sound {
+                Sound::Clip(clip) => sink.append(clip),
+                Sound::Tone { frequency, millis } => sink.append(
+                    SineWave::new(frequency)
+                        .take_duration(Duration::from_millis(millis))
+                        .amplify(0.2),
+                ),
+                Sound::None => {}
             }
+            sink.detach();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sound::SoundSettings;
+
+    #[test]
+    fn beeper_without_output_is_silent() {
+        let beeper = Beeper::default();
+        for event in SoundEvent::ALL {
+            beeper.play(event);
         }
+        beeper.play(SoundEvent::Metronome);
     }
 
-    pub fn play_beep(&self) {
-        self.play(&self.beep);
+    #[test]
+    fn beeper_is_created_with_or_without_audio_device() {
+        let theme = SoundTheme::load(&SoundSettings::default());
+        let beeper = Beeper::new(Arc::new(theme), 0.0);
+        beeper.play(SoundEvent::KeyPress);
     }
 }
@@ -0,0 +1,249 @@
+use iced::{
+    font::{Style, Weight},
+    widget::{self, column, container, row, text, text::Span},
+    Element, Length,
+};
+
+use crate::{
+    config::{Block, Config},
+    font, scale,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Emphasis {
+    bold: bool,
+    italic: bool,
+    code: bool,
+}
+
+// Splits text into parts with same emphasis.
+// Markers are **bold**, *italic* or _italic_ and `code`.
+// Marker, which can not open or close emphasis, is kept as text.
+fn inline(text: &str) -> Vec<(String, Emphasis)> {
+    let mut parts = vec![];
+    let mut current = String::new();
+    let mut emphasis = Emphasis::default();
+    let chars: Vec<char> = text.chars().collect();
+    let mut index = 0;
+    while index < chars.len() {
+        let ch = chars[index];
+        let mut next_emphasis = emphasis;
+        let mut marker_len = 1;
+        if ch == '`' {
+            next_emphasis.code = !emphasis.code;
+        } else if emphasis.code || (ch != '*' && ch != '_') {
+            marker_len = 0;
+        } else {
+            let len = if ch == '*' && chars.get(index + 1) == Some(&'*') {
+                2
+            } else {
+                1
+            };
+            let active = if len == 2 {
+                emphasis.bold
+            } else {
+                emphasis.italic
+            };
+            let toggles = if active {
+                closes(&chars, index, len)
+            } else {
+                opens(&chars, index, len) && has_closer(&chars, index + len, ch, len)
+            };
+            if !toggles {
+                current.extend(&chars[index..index + len]);
+                index += len;
+                continue;
+            }
+            if len == 2 {
+                next_emphasis.bold = !active;
+            } else {
+                next_emphasis.italic = !active;
+            }
+            marker_len = len;
+        }
+        if marker_len == 0 {
+            current.push(ch);
+            index += 1;
+            continue;
+        }
+        if !current.is_empty() {
+            parts.push((std::mem::take(&mut current), emphasis));
+        }
+        emphasis = next_emphasis;
+        index += marker_len;
+    }
+    if !current.is_empty() {
+        parts.push((current, emphasis));
+    }
+    parts
+}
+
+// Marker opens emphasis before word, so 2*3, a * b or snake_case stay as typed
+fn opens(chars: &[char], index: usize, len: usize) -> bool {
+    let previous = index.checked_sub(1).map(|i| chars[i]);
+    !previous.is_some_and(char::is_alphanumeric)
+        && chars.get(index + len).is_some_and(|ch| !ch.is_whitespace())
+}
+
+// Marker closes emphasis after word
+fn closes(chars: &[char], index: usize, len: usize) -> bool {
+    let previous = index.checked_sub(1).map(|i| chars[i]);
+    previous.is_some_and(|ch| !ch.is_whitespace())
+        && !chars
+            .get(index + len)
+            .is_some_and(|ch| ch.is_alphanumeric())
+}
+
+// Opening marker without closing one is not a marker
+fn has_closer(chars: &[char], from: usize, marker: char, len: usize) -> bool {
+    (from..chars.len()).any(|index| {
+        let run = chars[index..]
+            .iter()
+            .take_while(|ch| **ch == marker)
+            .count();
+        let starts_run = index == 0 || chars[index - 1] != marker;
+        starts_run && run == len && closes(chars, index, len)
+    })
+}
+
+// Text without emphasis markers
+pub fn plain_text(text: &str) -> String {
+    inline(text).into_iter().map(|(part, _)| part).collect()
+}
+
+// Text with emphasis
+pub fn rich<'a, M: Clone + 'a>(content: &str, size: f32) -> Element<'a, M> {
+    let ui: iced::Font = font::UI.clone().into();
+    let spans: Vec<Span<'a, M>> = inline(content)
+        .into_iter()
+        .map(|(part, emphasis)| {
+            let font = if emphasis.code {
+                font::MONO.clone().into()
+            } else {
+                iced::Font {
+                    weight: if emphasis.bold {
+                        Weight::Bold
+                    } else {
+                        ui.weight
+                    },
+                    style: if emphasis.italic {
+                        Style::Italic
+                    } else {
+                        ui.style
+                    },
+                    ..ui
+                }
+            };
+            widget::span(part).size(size).font(font)
+        })
+        .collect();
+    widget::rich_text(spans).into()
+}
+
+fn bold() -> iced::Font {
+    iced::Font {
+        weight: Weight::Bold,
+        ..font::UI.clone().into()
+    }
+}
+
+// Picture from data directory
+pub fn image<'a, M: 'a>(file: &str, height: f32) -> Element<'a, M> {
+    let path = Config::data_dir().join(file);
+    if file.to_lowercase().ends_with(".svg") {
+        widget::svg(widget::svg::Handle::from_path(path))
+            .height(Length::Fixed(height))
+            .into()
+    } else {
+        widget::image(path).height(Length::Fixed(height)).into()
+    }
+}
+
+// Text block of page. Keyboard and exercises are shown by application.
+pub fn view<'a, M: Clone + 'a>(block: &Block, config: &Config) -> Element<'a, M> {
+    let text_size = config.text_size(scale::TEXT_SIZE);
+    match block {
+        Block::Heading(heading) => text(heading.clone())
+            .size(config.text_size(scale::HEADING_SIZE))
+            .font(bold())
+            .into(),
+        Block::Paragraph(paragraph) => rich(paragraph, text_size),
+        Block::List(items) => column(items.iter().map(|item| {
+            row![text("•").size(text_size), rich(item, text_size)]
+                .spacing(text_size / 2.0)
+                .into()
+        }))
+        .spacing(text_size / 4.0)
+        .into(),
+        Block::Image(file) => image(file, config.text_size(scale::IMAGE_HEIGHT)),
+        Block::Callout {
+            kind,
+            text: callout,
+        } => container(
+            column![
+                text(kind.label()).size(text_size).font(bold()),
+                rich(callout, text_size)
+            ]
+            .spacing(text_size / 4.0),
+        )
+        .padding(text_size / 2.0)
+        .width(Length::Fill)
+        .style(container::bordered_box)
+        .into(),
+        // Markdown is split into blocks, when lesson is loaded
+        Block::Markdown(_) | Block::Keyboard | Block::Exercises => column![].into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(text: &str, bold: bool, italic: bool, code: bool) -> (String, Emphasis) {
+        (text.to_string(), Emphasis { bold, italic, code })
+    }
+
+    #[test]
+    fn bold_italic_and_code() {
+        assert_eq!(
+            inline("**Bold**, *italic*, _italic_ and `code`."),
+            vec![
+                part("Bold", true, false, false),
+                part(", ", false, false, false),
+                part("italic", false, true, false),
+                part(", ", false, false, false),
+                part("italic", false, true, false),
+                part(" and ", false, false, false),
+                part("code", false, false, true),
+                part(".", false, false, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn lone_star_is_text() {
+        assert_eq!(
+            inline("2*3 = 6"),
+            vec![part("2*3 = 6", false, false, false)]
+        );
+        assert_eq!(inline("a * b"), vec![part("a * b", false, false, false)]);
+        assert_eq!(
+            inline("*not closed"),
+            vec![part("*not closed", false, false, false)]
+        );
+        assert_eq!(inline("2**3"), vec![part("2**3", false, false, false)]);
+    }
+
+    #[test]
+    fn underscore_inside_word_is_text() {
+        assert_eq!(
+            inline("snake_case_name"),
+            vec![part("snake_case_name", false, false, false)]
+        );
+    }
+
+    #[test]
+    fn markers_inside_code_are_text() {
+        assert_eq!(inline("`a*b_c`"), vec![part("a*b_c", false, false, true)]);
+    }
+}
@@ -1,16 +1,27 @@
+mod block;
 mod exercise;
 mod index;
+mod keybindings;
 mod lesson;
+mod progress;
 
 use index::Index;
 use serde::{Deserialize, Serialize};
-use std::{fs, path::PathBuf};
+use std::{collections::HashMap, fs, path::PathBuf};
 use thiserror::Error;
 
-use crate::{environment, Result};
+use crate::{
+    custom_text, environment, font, keyboard_config::PressedKeyCoord, scale, sound::SoundSettings,
+    Result,
+};
+pub use block::{parse_markdown, Block, CalloutKind};
+pub use exercise::KeyFilter;
 pub use index::IndexRecord;
+pub use keybindings::{Action, Keybindings};
 pub use lesson::Exercise;
 pub use lesson::Lesson;
+pub use lesson::LessonPage;
+pub use progress::{LessonStatus, Progress};
 
 #[derive(Deserialize, Serialize, Default)]
 pub struct Configuration {
@@ -22,6 +33,64 @@ pub struct Configuration {
     current_page: usize,
     #[serde(default)]
     current_exercise: usize,
+    // Typed text of exercise lines, restored on next start
+    #[serde(default)]
+    current_input: Vec<String>,
+    // Seed of random exercise lines, so restored input matches them
+    #[serde(default)]
+    current_seed: Option<u64>,
+    #[serde(default = "default_ui_scale")]
+    ui_scale: f32,
+    #[serde(default = "default_exercise_font")]
+    exercise_font: String,
+    #[serde(default)]
+    ui_font: String,
+    #[serde(default)]
+    accessibility: Accessibility,
+    #[serde(default)]
+    sound: SoundSettings,
+    #[serde(default)]
+    student_name: String,
+    #[serde(default)]
+    gates: GateMode,
+    #[serde(default)]
+    keybindings: Keybindings,
+}
+
+// How page pass thresholds are applied
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq)]
+pub enum GateMode {
+    // Student is offered to repeat exercise, but can continue
+    #[default]
+    Advisory,
+    // Student has to repeat exercise until thresholds are met
+    Mandatory,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct Accessibility {
+    // Cursor is shown all the time instead of blinking
+    #[serde(default)]
+    pub steady_cursor: bool,
+    // Keys to learn are highlighted instead of flashing on keyboard
+    #[serde(default)]
+    pub static_key_hints: bool,
+    // Use dyslexia friendly font instead of selected fonts
+    #[serde(default)]
+    pub dyslexia_font: bool,
+    // Additional space between exercise letters in pixels
+    #[serde(default)]
+    pub letter_spacing: f32,
+    #[serde(default)]
+    pub high_contrast: bool,
+}
+
+fn default_ui_scale() -> f32 {
+    scale::DEFAULT
+}
+
+fn default_exercise_font() -> String {
+    font::DEFAULT_FAMILY.to_string()
 }
 
 #[derive(Debug, Clone, Default)]
@@ -31,6 +100,24 @@ pub struct Config {
     pub current_lesson: String,
     pub current_page: usize,
     pub current_exercise: usize,
+    pub current_input: Vec<String>,
+    pub current_seed: Option<u64>,
+    pub ui_scale: f32,
+    pub exercise_font: String,
+    // Empty for iced default font
+    pub ui_font: String,
+    pub accessibility: Accessibility,
+    pub sound: SoundSettings,
+    pub student_name: String,
+    pub gates: GateMode,
+    pub keybindings: Keybindings,
+    // Bindings of config file replaced with defaults, saved back unchanged
+    invalid_keybindings: Option<Keybindings>,
+    // Problems of config files, shown in app
+    pub load_errors: Vec<String>,
+    pub progress: Progress,
+    // Keys shown on pages of lessons, by lesson file
+    lesson_keys: HashMap<String, Vec<PressedKeyCoord>>,
 }
 
 impl Config {
@@ -53,6 +140,10 @@ impl Config {
         Self::config_dir().join(environment::CONFIG_FILE_NAME)
     }
 
+    fn progress_path() -> PathBuf {
+        Self::config_dir().join(environment::PROGRESS_FILE_NAME)
+    }
+
     pub fn load() -> Result<Self> {
         let path = Self::path();
         let Configuration {
@@ -60,6 +151,16 @@ impl Config {
             current_lesson,
             current_page,
             current_exercise,
+            current_input,
+            current_seed,
+            ui_scale,
+            exercise_font,
+            ui_font,
+            accessibility,
+            sound,
+            student_name,
+            gates,
+            keybindings,
         } = if path.exists() {
             let content = fs::read_to_string(path).map_err(|e| Error::Read(e.to_string()))?;
             serde_yaml::from_str(content.as_ref()).map_err(|e| Error::Parse(e.to_string()))?
@@ -67,26 +168,94 @@ impl Config {
             Configuration {
                 current_keyboard: "querty".to_string(),
                 current_lesson: "".to_string(),
+                ui_scale: scale::DEFAULT,
+                exercise_font: default_exercise_font(),
                 ..Configuration::default()
             }
         };
 
+        let mut load_errors = vec![];
+        // Invalid bindings are reported in app, defaults are used meanwhile
+        let (keybindings, invalid_keybindings) = match keybindings.validate() {
+            Ok(warnings) => {
+                load_errors.extend(warnings);
+                (keybindings, None)
+            }
+            Err(err) => {
+                load_errors.push(format!("{}, default key bindings are used", err));
+                (Keybindings::default(), Some(keybindings))
+            }
+        };
         let index = Index::load(Self::data_dir().join("index.yaml"))?;
+        // Broken progress file is copied aside, so it is not lost on save
+        let progress = match Progress::load(Self::progress_path()) {
+            Ok(progress) => progress,
+            Err(err) => {
+                let backup = Self::progress_path().with_extension("yaml.bak");
+                let error = format!(
+                    "{}: {}. Progress starts from scratch",
+                    environment::PROGRESS_FILE_NAME,
+                    err
+                );
+                load_errors.push(match fs::copy(Self::progress_path(), &backup) {
+                    Ok(_) => format!("{}, old file was copied to {}", error, backup.display()),
+                    Err(_) => error,
+                });
+                Progress::default()
+            }
+        };
         Ok(Config {
             index,
             current_keyboard,
             current_lesson,
             current_page,
             current_exercise,
+            current_input,
+            current_seed,
+            ui_scale: scale::clamp(ui_scale),
+            exercise_font,
+            ui_font,
+            accessibility,
+            sound,
+            student_name,
+            gates,
+            keybindings,
+            invalid_keybindings,
+            load_errors,
+            progress,
+            lesson_keys: HashMap::new(),
         })
     }
 
     pub async fn save(self) -> core::result::Result<(), Error> {
+        // Custom text is not kept between sessions, so picker is shown on next start
+        let custom = self.is_custom_lesson();
         let config_to_save = Configuration {
             current_keyboard: self.current_keyboard.clone(),
-            current_lesson: self.current_lesson.clone(),
-            current_page: self.current_page,
-            current_exercise: self.current_exercise,
+            current_lesson: if custom {
+                String::new()
+            } else {
+                self.current_lesson.clone()
+            },
+            current_page: if custom { 0 } else { self.current_page },
+            current_exercise: if custom { 0 } else { self.current_exercise },
+            current_input: if custom {
+                vec![]
+            } else {
+                self.current_input.clone()
+            },
+            current_seed: if custom { None } else { self.current_seed },
+            ui_scale: self.ui_scale,
+            exercise_font: self.exercise_font.clone(),
+            ui_font: self.ui_font.clone(),
+            accessibility: self.accessibility.clone(),
+            sound: self.sound.clone(),
+            student_name: self.student_name.clone(),
+            gates: self.gates,
+            keybindings: self
+                .invalid_keybindings
+                .clone()
+                .unwrap_or_else(|| self.keybindings.clone()),
         };
         let config =
             serde_yaml::to_string(&config_to_save).map_err(|e| Error::Parse(e.to_string()))?;
@@ -94,14 +263,55 @@ impl Config {
         tokio::fs::write(path, &config)
             .await
             .map_err(|e| Error::Write(e.to_string()))?;
+        self.progress
+            .save(Self::progress_path())
+            .await
+            .map_err(|e| Error::Write(e.to_string()))?;
         Ok(())
     }
 
-    // If current_page goes out of index, lesson is considered finished
-    // and index page is shown.
-    pub fn next_page(&mut self) {
+    // Sets current page and starts from its first exercise
+    pub fn go_to_page(&mut self, page: usize) {
         self.current_exercise = 0;
-        self.current_page += 1;
+        self.current_page = page;
+    }
+
+    // Scaled text size for given base size
+    pub fn text_size(&self, base: f32) -> f32 {
+        base * self.ui_scale
+    }
+
+    // Fonts to use at startup, taking accessibility settings into account
+    pub fn fonts(&self) -> (&str, &str) {
+        if self.accessibility.dyslexia_font {
+            (font::DYSLEXIA_FAMILY, font::DYSLEXIA_FAMILY)
+        } else {
+            (&self.exercise_font, &self.ui_font)
+        }
+    }
+
+    // Keys shown on pages of lessons preceding current one in index.
+    // Each lesson is read once, its keys are kept for later lessons.
+    pub fn keys_before_lesson(&mut self) -> Result<Vec<PressedKeyCoord>> {
+        let mut keys = vec![];
+        for record in &self.index.lessons {
+            if record.file == self.current_lesson {
+                break;
+            }
+            if !self.lesson_keys.contains_key(&record.file) {
+                let lesson = Lesson::load(Self::data_dir().join(format!("{}.yaml", record.file)))?;
+                self.lesson_keys.insert(
+                    record.file.clone(),
+                    lesson
+                        .pages
+                        .into_iter()
+                        .flat_map(|page| page.show_keys)
+                        .collect(),
+                );
+            }
+            keys.extend(self.lesson_keys[&record.file].iter().cloned());
+        }
+        Ok(keys)
     }
 
     pub fn load_lesson(&mut self, file_name: &str) -> Result<Lesson> {
@@ -111,6 +321,56 @@ impl Config {
         self.current_page = 0;
         Ok(lesson)
     }
+
+    // Starts session with user supplied text instead of lesson from index
+    pub fn load_custom_lesson(&mut self, name: &str, text: &str) -> Lesson {
+        self.current_lesson = format!("{}{}", custom_text::LESSON_PREFIX, name);
+        self.current_exercise = 0;
+        self.current_page = 0;
+        custom_text::lesson(name, text, self.keybindings.chord(Action::Next))
+    }
+
+    // Lessons to complete before given one can be started.
+    // Lesson in progress is never locked.
+    pub fn missing_prerequisites(
+        &self,
+        file: &str,
+        supported: impl Fn(&IndexRecord) -> bool,
+    ) -> Vec<&IndexRecord> {
+        if file == self.current_lesson {
+            return vec![];
+        }
+        self.index.missing_prerequisites(
+            file,
+            |required| self.progress.completed(required),
+            supported,
+        )
+    }
+
+    // Lesson left unfinished, which can be resumed from picker
+    pub fn resumable_lesson(&self) -> Option<&IndexRecord> {
+        if self.is_custom_lesson() {
+            return None;
+        }
+        // Lesson at first page is resumable only if some results were recorded
+        self.index.find(&self.current_lesson).filter(|_| {
+            self.current_page > 0
+                || self.progress.status(&self.current_lesson) == LessonStatus::InProgress
+        })
+    }
+
+    pub fn lesson_status(&self, file: &str) -> LessonStatus {
+        match self.progress.status(file) {
+            LessonStatus::NotStarted if file == self.current_lesson && self.current_page > 0 => {
+                LessonStatus::InProgress
+            }
+            status => status,
+        }
+    }
+
+    pub fn is_custom_lesson(&self) -> bool {
+        self.current_lesson.starts_with(custom_text::LESSON_PREFIX)
+    }
 }
 
 #[derive(Debug, Clone, Error)]
@@ -0,0 +1,286 @@
+use serde::Deserialize;
+
+// Part of lesson page. Pages with blocks show them in given order
+// instead of content, images, keyboard and exercises.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub enum Block {
+    Heading(String),
+    // Text with **bold**, *italic* and `code` emphasis
+    Paragraph(String),
+    List(Vec<String>),
+    // PNG or SVG picture from data directory
+    Image(String),
+    Callout {
+        #[serde(default)]
+        kind: CalloutKind,
+        text: String,
+    },
+    // Markdown text, which is split into headings, paragraphs, lists,
+    // images and callouts (> quotes)
+    Markdown(String),
+    Keyboard,
+    Exercises,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+pub enum CalloutKind {
+    #[default]
+    Note,
+    Tip,
+    Warning,
+}
+
+impl CalloutKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CalloutKind::Note => "Note",
+            CalloutKind::Tip => "Tip",
+            CalloutKind::Warning => "Warning",
+        }
+    }
+}
+
+impl Block {
+    // Block texts, which are page templates
+    pub fn texts(&self) -> Vec<&String> {
+        match self {
+            Block::Heading(text)
+            | Block::Paragraph(text)
+            | Block::Markdown(text)
+            | Block::Callout { text, .. } => vec![text],
+            Block::List(items) => items.iter().collect(),
+            Block::Image(_) | Block::Keyboard | Block::Exercises => vec![],
+        }
+    }
+
+    // Same block with texts replaced by rendered ones
+    pub fn with_texts(&self, mut texts: impl Iterator<Item = String>) -> Block {
+        let mut next = || texts.next().unwrap_or_default();
+        match self {
+            Block::Heading(_) => Block::Heading(next()),
+            Block::Paragraph(_) => Block::Paragraph(next()),
+            Block::Markdown(_) => Block::Markdown(next()),
+            Block::Callout { kind, .. } => Block::Callout {
+                kind: *kind,
+                text: next(),
+            },
+            Block::List(items) => Block::List(items.iter().map(|_| next()).collect()),
+            block => block.clone(),
+        }
+    }
+}
+
+// Replaces markdown blocks with blocks markdown consists of
+pub fn expand_markdown(blocks: Vec<Block>) -> Vec<Block> {
+    blocks
+        .into_iter()
+        .flat_map(|block| match block {
+            Block::Markdown(markdown) => parse_markdown(&markdown),
+            block => vec![block],
+        })
+        .collect()
+}
+
+// Splits markdown text into page blocks. Supported are headings (#),
+// bullet and numbered lists, pictures on separate line (![alt](file)),
+// quotes as callouts (> [!TIP] on first line sets kind) and paragraphs.
+pub fn parse_markdown(markdown: &str) -> Vec<Block> {
+    let mut blocks = vec![];
+    let mut paragraph: Vec<&str> = vec![];
+    let mut items: Vec<String> = vec![];
+    let mut quote: Vec<&str> = vec![];
+
+    fn flush(
+        blocks: &mut Vec<Block>,
+        paragraph: &mut Vec<&str>,
+        items: &mut Vec<String>,
+        quote: &mut Vec<&str>,
+    ) {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(paragraph.join(" ")));
+            paragraph.clear();
+        }
+        if !items.is_empty() {
+            blocks.push(Block::List(std::mem::take(items)));
+        }
+        if !quote.is_empty() {
+            let mut kind = CalloutKind::Note;
+            if let Some(first) = quote.first() {
+                let marker = match first.to_uppercase().as_str() {
+                    "[!NOTE]" => Some(CalloutKind::Note),
+                    "[!TIP]" => Some(CalloutKind::Tip),
+                    "[!WARNING]" => Some(CalloutKind::Warning),
+                    _ => None,
+                };
+                if let Some(marker) = marker {
+                    kind = marker;
+                    quote.remove(0);
+                }
+            }
+            blocks.push(Block::Callout {
+                kind,
+                text: quote.join(" "),
+            });
+            quote.clear();
+        }
+    }
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            flush(&mut blocks, &mut paragraph, &mut items, &mut quote);
+        } else if let Some(title) = heading(trimmed) {
+            flush(&mut blocks, &mut paragraph, &mut items, &mut quote);
+            blocks.push(Block::Heading(title.to_string()));
+        } else if let Some(file) = trimmed
+            .strip_prefix("![")
+            .and_then(|rest| rest.split_once("]("))
+            .and_then(|(_, rest)| rest.strip_suffix(')'))
+        {
+            flush(&mut blocks, &mut paragraph, &mut items, &mut quote);
+            blocks.push(Block::Image(file.to_string()));
+        } else if let Some(text) = trimmed.strip_prefix('>') {
+            if quote.is_empty() {
+                flush(&mut blocks, &mut paragraph, &mut items, &mut quote);
+            }
+            quote.push(text.trim());
+        } else if let Some(item) = list_item(trimmed) {
+            if items.is_empty() {
+                flush(&mut blocks, &mut paragraph, &mut items, &mut quote);
+            }
+            items.push(item.to_string());
+        } else if !items.is_empty() && line.starts_with(char::is_whitespace) {
+            // Indented line continues list item
+            if let Some(item) = items.last_mut() {
+                item.push(' ');
+                item.push_str(trimmed);
+            }
+        } else {
+            if !items.is_empty() || !quote.is_empty() {
+                flush(&mut blocks, &mut paragraph, &mut items, &mut quote);
+            }
+            paragraph.push(trimmed);
+        }
+    }
+    flush(&mut blocks, &mut paragraph, &mut items, &mut quote);
+    blocks
+}
+
+// Heading is # followed by space, so #hashtag stays text
+fn heading(line: &str) -> Option<&str> {
+    let title = line.trim_start_matches('#');
+    let level = line.len() - title.len();
+    ((1..=6).contains(&level) && title.starts_with(' ')).then(|| title.trim())
+}
+
+fn list_item(line: &str) -> Option<&str> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(item) = line.strip_prefix(bullet) {
+            return Some(item.trim());
+        }
+    }
+    let (number, item) = line.split_once(". ")?;
+    (!number.is_empty() && number.chars().all(|ch| ch.is_ascii_digit())).then(|| item.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_headings_paragraphs_and_images() {
+        let blocks =
+            parse_markdown("# Title\n\nFirst line\nsecond line\n\n![Sitting](images/sitting.svg)");
+        assert_eq!(
+            blocks,
+            vec![
+                Block::Heading("Title".to_string()),
+                Block::Paragraph("First line second line".to_string()),
+                Block::Image("images/sitting.svg".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn hash_without_space_is_not_heading() {
+        let blocks = parse_markdown("## Symbols\n\n#include <stdio.h> and #hashtag");
+        assert_eq!(
+            blocks,
+            vec![
+                Block::Heading("Symbols".to_string()),
+                Block::Paragraph("#include <stdio.h> and #hashtag".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_bullet_and_numbered_lists() {
+        let blocks = parse_markdown("- one\n* two\n  continued\n+ three\n\n1. first\n10. tenth");
+        assert_eq!(
+            blocks,
+            vec![
+                Block::List(vec![
+                    "one".to_string(),
+                    "two continued".to_string(),
+                    "three".to_string()
+                ]),
+                Block::List(vec!["first".to_string(), "tenth".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn paragraph_after_list_is_separate_block() {
+        let blocks = parse_markdown("- item\nText");
+        assert_eq!(
+            blocks,
+            vec![
+                Block::List(vec!["item".to_string()]),
+                Block::Paragraph("Text".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn not_numbered_line_is_paragraph() {
+        let blocks = parse_markdown("3.14 is pi. Really");
+        assert_eq!(
+            blocks,
+            vec![Block::Paragraph("3.14 is pi. Really".to_string())]
+        );
+    }
+
+    #[test]
+    fn callout_marker_sets_kind() {
+        let blocks = parse_markdown("> [!tip]\n> Relax\n> shoulders\n\n> Plain quote");
+        assert_eq!(
+            blocks,
+            vec![
+                Block::Callout {
+                    kind: CalloutKind::Tip,
+                    text: "Relax shoulders".to_string()
+                },
+                Block::Callout {
+                    kind: CalloutKind::Note,
+                    text: "Plain quote".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn expands_only_markdown_blocks() {
+        let blocks = expand_markdown(vec![
+            Block::Markdown("# Title\n\nText".to_string()),
+            Block::Exercises,
+        ]);
+        assert_eq!(
+            blocks,
+            vec![
+                Block::Heading("Title".to_string()),
+                Block::Paragraph("Text".to_string()),
+                Block::Exercises,
+            ]
+        );
+    }
+}
@@ -6,4 +6,138 @@ pub enum Exercise {
     None,
     OneLineNoEnter(String),
     Multiline(String),
+    // Prose wrapped to window width. Lines flow into each other while typing.
+    Paragraph(String),
+    // Exercise with metronome beat at given rate (keys per minute)
+    Metronome {
+        kpm: u32,
+        exercise: Box<Exercise>,
+    },
+    // Exercise repeated given number of times and/or until
+    // target accuracy is reached in_a_row times
+    Repeat {
+        #[serde(default)]
+        times: Option<u32>,
+        #[serde(default)]
+        until_accuracy: Option<f64>,
+        #[serde(default = "default_in_a_row")]
+        in_a_row: u32,
+        exercise: Box<Exercise>,
+    },
+    // Lines (one per text line) in random order. If pick is set,
+    // only that many lines are used.
+    Shuffle {
+        lines: String,
+        #[serde(default)]
+        pick: Option<usize>,
+        #[serde(default)]
+        seed: Option<u64>,
+    },
+    // Lines of words randomly chosen from words separated by whitespace
+    WordPool {
+        words: String,
+        lines: usize,
+        #[serde(default = "default_words_per_line")]
+        words_per_line: usize,
+        #[serde(default)]
+        seed: Option<u64>,
+    },
+    // Lines of words randomly chosen from word list or text file in data directory
+    WordList {
+        file: String,
+        lines: usize,
+        #[serde(default = "default_words_per_line")]
+        words_per_line: usize,
+        #[serde(default)]
+        filter: KeyFilter,
+        #[serde(default)]
+        max_length: Option<usize>,
+        #[serde(default)]
+        seed: Option<u64>,
+    },
+    // Key chords like Ctrl+C or Ctrl+Shift+T pressed one after another
+    Shortcuts(Vec<String>),
+    // Source code snippet typed line by line, <Enter> ends line.
+    // Code is given inline or loaded from file in data directory.
+    Code {
+        #[serde(default)]
+        language: String,
+        #[serde(default)]
+        code: String,
+        #[serde(default)]
+        file: Option<String>,
+        // Leading indentation of each line is filled in automatically
+        #[serde(default = "default_auto_indent")]
+        auto_indent: bool,
+        // Spaces inserted by <Tab>
+        #[serde(default = "default_indent")]
+        indent: usize,
+    },
+}
+
+impl Exercise {
+    // Exercise typed by student, without metronome and repeat wrappers
+    pub fn typed(&self) -> &Exercise {
+        match self {
+            Exercise::Metronome { exercise, .. } | Exercise::Repeat { exercise, .. } => {
+                exercise.typed()
+            }
+            exercise => exercise,
+        }
+    }
+}
+
+// Which words of word list can be used in exercise
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+pub enum KeyFilter {
+    // All words
+    #[default]
+    All,
+    // Words, which can be typed with keys introduced so far
+    Introduced,
+    // Words, which consist only of given characters
+    Keys(String),
+}
+
+fn default_words_per_line() -> usize {
+    8
+}
+
+fn default_in_a_row() -> u32 {
+    1
+}
+
+fn default_auto_indent() -> bool {
+    true
+}
+
+fn default_indent() -> usize {
+    4
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_exercise_is_unwrapped() {
+        let code = Exercise::Code {
+            language: String::from("rust"),
+            code: String::from("fn main() {}"),
+            file: None,
+            auto_indent: true,
+            indent: 4,
+        };
+        let wrapped = Exercise::Repeat {
+            times: Some(2),
+            until_accuracy: None,
+            in_a_row: 1,
+            exercise: Box::new(Exercise::Metronome {
+                kpm: 120,
+                exercise: Box::new(code.clone()),
+            }),
+        };
+        assert_eq!(wrapped.typed(), &code);
+        assert_eq!(code.typed(), &code);
+    }
 }
@@ -6,19 +6,109 @@ use thiserror::Error;
 pub struct IndexRecord {
     pub file: String,
     pub title: String,
+    #[serde(default)]
+    pub description: String,
+    // Estimated duration in minutes
+    #[serde(default)]
+    pub minutes: Option<u32>,
+    // Lesson files, which must be completed before this lesson
+    #[serde(default)]
+    pub requires: Vec<String>,
+    // Lesson needs keyboard with numeric keypad
+    #[serde(default)]
+    pub numpad: bool,
+    // Title of section lesson belongs to, set on load
+    #[serde(skip)]
+    pub section: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct Section {
+    pub title: String,
+    pub lessons: Vec<IndexRecord>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, PartialEq)]
+pub enum Unlock {
+    // All lessons are open, except ones with requires
+    #[default]
+    Open,
+    // Every lesson also requires previous one
+    Sequential,
 }
 
 #[derive(Debug, Default, Clone, Deserialize)]
 pub struct Index {
+    // Lessons in course order. Lessons of sections are appended on load.
+    #[serde(default)]
     pub lessons: Vec<IndexRecord>,
+    #[serde(default)]
+    sections: Vec<Section>,
+    #[serde(default)]
+    pub unlock: Unlock,
 }
 
 impl Index {
     pub fn load(path: PathBuf) -> Result<Self, Error> {
         let content = fs::read_to_string(path).map_err(|e| Error::Read(e.to_string()))?;
-        let lesson: Index =
-            serde_yaml::from_str(&content).map_err(|e| Error::Parse(e.to_string()))?;
-        Ok(lesson)
+        Self::parse(&content)
+    }
+
+    // Flattens sections into lesson list and checks prerequisites exist
+    fn parse(content: &str) -> Result<Self, Error> {
+        let mut index: Index =
+            serde_yaml::from_str(content).map_err(|e| Error::Parse(e.to_string()))?;
+        for section in std::mem::take(&mut index.sections) {
+            for mut record in section.lessons {
+                record.section = section.title.clone();
+                index.lessons.push(record);
+            }
+        }
+        for record in &index.lessons {
+            if let Some(file) = record
+                .requires
+                .iter()
+                .find(|file| index.find(file).is_none())
+            {
+                return Err(Error::UnknownLesson(record.file.clone(), file.clone()));
+            }
+        }
+        Ok(index)
+    }
+
+    pub fn find(&self, file: &str) -> Option<&IndexRecord> {
+        self.lessons.iter().find(|record| record.file == file)
+    }
+
+    // Lessons, which must be completed before given one and are not yet.
+    // Sequential unlock passes over lessons not supported by keyboard.
+    pub fn missing_prerequisites(
+        &self,
+        file: &str,
+        completed: impl Fn(&str) -> bool,
+        supported: impl Fn(&IndexRecord) -> bool,
+    ) -> Vec<&IndexRecord> {
+        let Some(position) = self.lessons.iter().position(|record| record.file == file) else {
+            return vec![];
+        };
+        let mut required: Vec<&str> = self.lessons[position]
+            .requires
+            .iter()
+            .map(String::as_str)
+            .collect();
+        if self.unlock == Unlock::Sequential {
+            if let Some(previous) = self.lessons[..position]
+                .iter()
+                .rev()
+                .find(|record| supported(*record))
+            {
+                required.push(previous.file.as_str());
+            }
+        }
+        self.lessons
+            .iter()
+            .filter(|record| required.contains(&record.file.as_str()) && !completed(&record.file))
+            .collect()
     }
 
     pub fn next_lesson(&self, current_lesson: &str) -> Option<&str> {
@@ -42,4 +132,95 @@ pub enum Error {
     Read(String),
     #[error("{0}")]
     Parse(String),
+    #[error("Lesson {0} requires unknown lesson {1}")]
+    UnknownLesson(String, String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INDEX: &str = "
+lessons:
+  - file: intro
+    title: Introduction
+sections:
+  - title: Letters
+    lessons:
+      - file: home
+        title: Home row
+      - file: numbers
+        title: Numbers
+        numpad: true
+  - title: Review
+    lessons:
+      - file: review
+        title: Review
+        requires: [home]
+";
+
+    #[test]
+    fn section_lessons_are_appended_in_order() {
+        let index = Index::parse(INDEX).unwrap();
+        let lessons: Vec<(&str, &str)> = index
+            .lessons
+            .iter()
+            .map(|record| (record.file.as_str(), record.section.as_str()))
+            .collect();
+        assert_eq!(
+            lessons,
+            vec![
+                ("intro", ""),
+                ("home", "Letters"),
+                ("numbers", "Letters"),
+                ("review", "Review")
+            ]
+        );
+        assert_eq!(index.next_lesson("numbers"), Some("review"));
+    }
+
+    #[test]
+    fn unknown_required_lesson_is_error() {
+        let err = Index::parse(&INDEX.replace("[home]", "[missing]")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Lesson review requires unknown lesson missing"
+        );
+    }
+
+    #[test]
+    fn open_unlock_checks_only_requires() {
+        let index = Index::parse(INDEX).unwrap();
+        let missing = |file, completed: &[&str]| -> Vec<String> {
+            index
+                .missing_prerequisites(file, |lesson| completed.contains(&lesson), |_| true)
+                .iter()
+                .map(|record| record.file.clone())
+                .collect()
+        };
+        assert!(missing("numbers", &[]).is_empty());
+        assert_eq!(missing("review", &["numbers"]), vec!["home"]);
+        assert!(missing("review", &["home"]).is_empty());
+    }
+
+    #[test]
+    fn sequential_unlock_requires_previous_supported_lesson() {
+        let index = Index::parse(&format!("unlock: Sequential\n{}", INDEX)).unwrap();
+        let missing = |file, completed: &[&str], numpad: bool| -> Vec<String> {
+            index
+                .missing_prerequisites(
+                    file,
+                    |lesson| completed.contains(&lesson),
+                    |record| numpad || !record.numpad,
+                )
+                .iter()
+                .map(|record| record.file.clone())
+                .collect()
+        };
+        assert!(missing("intro", &[], true).is_empty());
+        assert_eq!(missing("home", &[], true), vec!["intro"]);
+        assert_eq!(missing("review", &["home"], true), vec!["numbers"]);
+        // Numpad lesson can not be completed without numpad, so it is passed over
+        assert!(missing("review", &["home"], false).is_empty());
+    }
 }
@@ -0,0 +1,218 @@
+use iced::keyboard::{Key, Modifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::shortcut_component::Chord;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    // Next page, when page exercises are finished. Confirms dialogs.
+    Next,
+    Back,
+    // Next page without recording results
+    Skip,
+    Pause,
+    Restart,
+    RestartLesson,
+    PageList,
+    // Exits lesson or app, closes dialogs
+    ExitLesson,
+    Stats,
+    ToggleKeyboard,
+    Help,
+    // Next page after failed exercise, when gates are advisory
+    Continue,
+}
+
+impl Action {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Next => "Next page",
+            Action::Back => "Previous page",
+            Action::Skip => "Skip page",
+            Action::Pause => "Pause",
+            Action::Restart => "Restart page",
+            Action::RestartLesson => "Restart lesson",
+            Action::PageList => "Page list",
+            Action::ExitLesson => "Exit lesson / close dialog",
+            Action::Stats => "Statistics",
+            Action::ToggleKeyboard => "Show / hide keyboard",
+            Action::Help => "Key bindings",
+            Action::Continue => "Continue after failed exercise",
+        }
+    }
+}
+
+// Global key bindings. Chords are written same way as in shortcut
+// exercises, e.g. Shift+Alt+Up, F1 or Mod+P.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Keybindings {
+    pub next: String,
+    pub back: String,
+    pub skip: String,
+    pub pause: String,
+    pub restart: String,
+    pub restart_lesson: String,
+    pub page_list: String,
+    pub exit_lesson: String,
+    pub stats: String,
+    pub toggle_keyboard: String,
+    pub help: String,
+    pub continue_anyway: String,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Keybindings {
+            next: "Enter".to_string(),
+            back: "Shift+Alt+Up".to_string(),
+            skip: "Shift+Alt+Down".to_string(),
+            pause: "F4".to_string(),
+            restart: "Shift+Alt+Backspace".to_string(),
+            restart_lesson: "Shift+Alt+Home".to_string(),
+            page_list: "Shift+Alt+End".to_string(),
+            exit_lesson: "Escape".to_string(),
+            stats: "F2".to_string(),
+            toggle_keyboard: "F3".to_string(),
+            help: "F1".to_string(),
+            continue_anyway: "Space".to_string(),
+        }
+    }
+}
+
+impl Keybindings {
+    pub fn bindings(&self) -> [(Action, &str); 12] {
+        [
+            (Action::Next, &self.next),
+            (Action::Back, &self.back),
+            (Action::Skip, &self.skip),
+            (Action::Pause, &self.pause),
+            (Action::Restart, &self.restart),
+            (Action::RestartLesson, &self.restart_lesson),
+            (Action::PageList, &self.page_list),
+            (Action::ExitLesson, &self.exit_lesson),
+            (Action::Stats, &self.stats),
+            (Action::ToggleKeyboard, &self.toggle_keyboard),
+            (Action::Help, &self.help),
+            (Action::Continue, &self.continue_anyway),
+        ]
+    }
+
+    // Same chord bound to several actions is an error. Chords without key and
+    // plain characters, which are typed in exercises, are returned as warnings.
+    pub fn validate(&self) -> Result<Vec<String>, String> {
+        let mut parsed: Vec<(Action, Chord)> = vec![];
+        let mut warnings = vec![];
+        for (action, binding) in self.bindings() {
+            let Some(chord) = Chord::parse(binding) else {
+                warnings.push(format!(
+                    "Key binding {} of {} has no key",
+                    binding,
+                    action.label()
+                ));
+                continue;
+            };
+            if chord.is_plain_character() {
+                warnings.push(format!(
+                    "Key binding {} of {} is typed in exercises, add Ctrl or Alt to it",
+                    binding,
+                    action.label()
+                ));
+            }
+            if let Some((other, _)) = parsed.iter().find(|(_, bound)| bound.same_keys(&chord)) {
+                return Err(format!(
+                    "Key binding {} is used for both {} and {}",
+                    binding,
+                    other.label(),
+                    action.label()
+                ));
+            }
+            parsed.push((action, chord));
+        }
+        Ok(warnings)
+    }
+
+    // Action bound to pressed key. Bindings, which can not be parsed, are ignored.
+    pub fn action(&self, key: &Key, modifiers: Modifiers) -> Option<Action> {
+        self.bindings()
+            .into_iter()
+            .find(|(_, chord)| {
+                Chord::parse(chord).is_some_and(|chord| chord.matches(key, modifiers))
+            })
+            .map(|(action, _)| action)
+    }
+
+    pub fn chord(&self, action: Action) -> &str {
+        self.bindings()
+            .into_iter()
+            .find(|(bound, _)| *bound == action)
+            .map_or("", |(_, chord)| chord)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::keyboard::key::Named;
+
+    #[test]
+    fn default_bindings_are_valid() {
+        assert_eq!(Keybindings::default().validate(), Ok(vec![]));
+    }
+
+    #[test]
+    fn typed_and_keyless_bindings_are_warnings() {
+        let keybindings = Keybindings {
+            pause: "p".to_string(),
+            stats: "Ctrl+Alt".to_string(),
+            ..Keybindings::default()
+        };
+        assert_eq!(
+            keybindings.validate(),
+            Ok(vec![
+                "Key binding p of Pause is typed in exercises, add Ctrl or Alt to it".to_string(),
+                "Key binding Ctrl+Alt of Statistics has no key".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn same_chord_for_two_actions_is_error() {
+        let keybindings = Keybindings {
+            stats: "f1".to_string(),
+            ..Keybindings::default()
+        };
+        assert_eq!(
+            keybindings.validate(),
+            Err("Key binding F1 is used for both Statistics and Key bindings".to_string())
+        );
+    }
+
+    #[test]
+    fn action_of_pressed_key() {
+        let keybindings = Keybindings {
+            pause: "Mod+P".to_string(),
+            ..Keybindings::default()
+        };
+        let command = if cfg!(target_os = "macos") {
+            Modifiers::LOGO
+        } else {
+            Modifiers::CTRL
+        };
+        assert_eq!(
+            keybindings.action(&Key::Character("p".into()), command),
+            Some(Action::Pause)
+        );
+        assert_eq!(
+            keybindings.action(&Key::Named(Named::F4), Modifiers::empty()),
+            None
+        );
+        assert_eq!(
+            keybindings.action(
+                &Key::Named(Named::ArrowUp),
+                Modifiers::SHIFT | Modifiers::ALT
+            ),
+            Some(Action::Back)
+        );
+    }
+}
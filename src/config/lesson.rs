@@ -2,22 +2,129 @@ use serde::Deserialize;
 use std::{fs, path::PathBuf};
 use thiserror::Error;
 
-use crate::keyboard_config::PressedKeyCoord;
+use crate::{keyboard_config::PressedKeyCoord, stats::Stats};
 
+use super::block::expand_markdown;
+pub use super::block::Block;
 pub use super::exercise::Exercise;
 
 #[derive(Debug, Default, Clone, Deserialize)]
 pub struct LessonPage {
+    // Name to refer page from branches
+    #[serde(default)]
+    pub id: Option<String>,
     pub title: String,
+    #[serde(default)]
     pub content: String,
+    // Page layout. If set, content, images and keyboard flag are not used.
+    #[serde(default)]
+    pub blocks: Vec<Block>,
     #[serde(default)]
     pub show_keys: Vec<PressedKeyCoord>,
     #[serde(default)]
     pub keyboard: bool,
+    // Show hands on keyboard with finger moving to show_keys
+    #[serde(default)]
+    pub hands: bool,
+    // PNG or SVG pictures from data directory shown after content
+    #[serde(default)]
+    pub images: Vec<String>,
     #[serde(default)]
     pub exercises: Vec<Exercise>,
     #[serde(default)]
     pub content2: String,
+    // Exercise pass thresholds. If not met, student is offered to repeat exercise.
+    #[serde(default)]
+    pub min_wpm: Option<f64>,
+    // Maximal errors in percents of exercise characters
+    #[serde(default)]
+    pub max_error_rate: Option<f64>,
+    // Text shown when thresholds are not met
+    #[serde(default = "default_fail_content")]
+    pub fail_content: String,
+    // Pages to go after this one. First branch with met condition is taken,
+    // otherwise lesson continues with following page.
+    #[serde(default)]
+    pub next: Vec<Branch>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Branch {
+    #[serde(default, rename = "if")]
+    pub condition: Condition,
+    pub goto: PageRef,
+}
+
+// Page number (starting from 1) or page id
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum PageRef {
+    Number(usize),
+    Id(String),
+}
+
+// Limits for results of last exercise finished in current lesson.
+// All given limits must be met, condition without limits is always met.
+// Condition with limits is not met, when there are no results yet
+// (no exercise finished since lesson start or page was skipped).
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct Condition {
+    #[serde(default)]
+    pub wpm_below: Option<f64>,
+    #[serde(default)]
+    pub wpm_above: Option<f64>,
+    #[serde(default)]
+    pub accuracy_below: Option<f64>,
+    #[serde(default)]
+    pub errors_above: Option<u64>,
+    #[serde(default)]
+    pub error_rate_above: Option<f64>,
+}
+
+impl Condition {
+    pub fn met(&self, stats: Option<&Stats>) -> bool {
+        let Some(stats) = stats else {
+            return self.is_empty();
+        };
+        self.wpm_below.is_none_or(|wpm| stats.wpm < wpm)
+            && self.wpm_above.is_none_or(|wpm| stats.wpm > wpm)
+            && self
+                .accuracy_below
+                .is_none_or(|accuracy| stats.accuracy < accuracy)
+            && self.errors_above.is_none_or(|errors| stats.errors > errors)
+            && self
+                .error_rate_above
+                .is_none_or(|error_rate| stats.error_rate > error_rate)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.wpm_below.is_none()
+            && self.wpm_above.is_none()
+            && self.accuracy_below.is_none()
+            && self.errors_above.is_none()
+            && self.error_rate_above.is_none()
+    }
+}
+
+fn default_fail_content() -> String {
+    "Your speed was {{wpm}} wpm with {{error_rate}}% errors.\n\
+     {{#if min_wpm}}Required speed is {{min_wpm}} wpm.\n{{/if}}\
+     {{#if max_error_rate}}Allowed errors are {{max_error_rate}}%.{{/if}}"
+        .to_string()
+}
+
+impl LessonPage {
+    pub fn has_gate(&self) -> bool {
+        self.min_wpm.is_some() || self.max_error_rate.is_some()
+    }
+
+    // Checks exercise results against page thresholds
+    pub fn passed(&self, stats: &Stats) -> bool {
+        self.min_wpm.is_none_or(|min_wpm| stats.wpm >= min_wpm)
+            && self
+                .max_error_rate
+                .is_none_or(|max_error_rate| stats.error_rate <= max_error_rate)
+    }
 }
 
 #[derive(Debug, Default, Clone, Deserialize)]
@@ -29,11 +136,41 @@ impl Lesson {
     pub fn load(path: PathBuf) -> Result<Self, Error> {
         let content = fs::read_to_string(path.clone())
             .map_err(|e| Error::Read(path.display().to_string(), e.to_string()))?;
-        let lesson: Lesson =
+        let mut lesson: Lesson =
             serde_yaml::from_str(&content).map_err(|e| Error::Parse(e.to_string()))?;
+        // Markdown is parsed once here, not on every redraw
+        for page in &mut lesson.pages {
+            page.blocks = expand_markdown(std::mem::take(&mut page.blocks));
+        }
+        for (index, page) in lesson.pages.iter().enumerate() {
+            for branch in &page.next {
+                if lesson.page_index(&branch.goto).is_none() {
+                    return Err(Error::UnknownPage(index + 1, format!("{:?}", branch.goto)));
+                }
+            }
+        }
         Ok(lesson)
     }
 
+    pub fn page_index(&self, page: &PageRef) -> Option<usize> {
+        match page {
+            PageRef::Number(number) => (1..=self.pages.len()).contains(number).then(|| number - 1),
+            PageRef::Id(id) => self
+                .pages
+                .iter()
+                .position(|page| page.id.as_ref() == Some(id)),
+        }
+    }
+
+    // Page to show after given one, taking page branches into account.
+    // Index past last page means lesson is finished.
+    pub fn next_page(&self, current_page: usize, stats: Option<&Stats>) -> usize {
+        self.get_page(current_page)
+            .and_then(|page| page.next.iter().find(|branch| branch.condition.met(stats)))
+            .and_then(|branch| self.page_index(&branch.goto))
+            .unwrap_or(current_page + 1)
+    }
+
     pub fn get_page(&self, page_index: usize) -> Option<&LessonPage> {
         self.pages.get(page_index)
     }
@@ -52,4 +189,100 @@ pub enum Error {
     Read(String, String),
     #[error("{0}")]
     Parse(String),
+    #[error("Page {0} goes to unknown page {1}")]
+    UnknownPage(usize, String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(wpm: f64, accuracy: f64) -> Stats {
+        Stats {
+            wpm,
+            accuracy,
+            ..Default::default()
+        }
+    }
+
+    fn lesson() -> Lesson {
+        serde_yaml::from_str(
+            "pages:
+               - title: Exercise
+                 next:
+                   - if:
+                       wpm_below: 15
+                     goto: remedial
+                   - if:
+                       accuracy_below: 90
+                     goto: 1
+               - title: Following
+               - title: Remedial
+                 id: remedial
+                 next:
+                   - goto: 2",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn condition_without_limits_is_always_met() {
+        let condition = Condition::default();
+        assert!(condition.met(None));
+        assert!(condition.met(Some(&stats(0.0, 0.0))));
+    }
+
+    #[test]
+    fn all_limits_must_be_met() {
+        let condition = Condition {
+            wpm_above: Some(20.0),
+            accuracy_below: Some(95.0),
+            ..Default::default()
+        };
+        assert!(condition.met(Some(&stats(25.0, 90.0))));
+        assert!(!condition.met(Some(&stats(15.0, 90.0))));
+        assert!(!condition.met(Some(&stats(25.0, 97.0))));
+    }
+
+    #[test]
+    fn condition_with_limits_is_not_met_without_results() {
+        let condition = Condition {
+            wpm_below: Some(15.0),
+            ..Default::default()
+        };
+        assert!(!condition.met(None));
+    }
+
+    #[test]
+    fn first_met_branch_is_taken() {
+        let lesson = lesson();
+        assert_eq!(lesson.next_page(0, Some(&stats(10.0, 80.0))), 2);
+        assert_eq!(lesson.next_page(0, Some(&stats(20.0, 80.0))), 0);
+    }
+
+    #[test]
+    fn following_page_without_met_branch() {
+        let lesson = lesson();
+        assert_eq!(lesson.next_page(0, Some(&stats(20.0, 95.0))), 1);
+        assert_eq!(lesson.next_page(1, Some(&stats(10.0, 80.0))), 2);
+    }
+
+    #[test]
+    fn branches_with_limits_are_passed_without_results() {
+        let lesson = lesson();
+        assert_eq!(lesson.next_page(0, None), 1);
+        assert_eq!(lesson.next_page(2, None), 1);
+    }
+
+    #[test]
+    fn page_refs_resolve_to_index() {
+        let lesson = lesson();
+        assert_eq!(lesson.page_index(&PageRef::Number(3)), Some(2));
+        assert_eq!(lesson.page_index(&PageRef::Number(0)), None);
+        assert_eq!(
+            lesson.page_index(&PageRef::Id("remedial".to_string())),
+            Some(2)
+        );
+        assert_eq!(lesson.page_index(&PageRef::Id("missing".to_string())), None);
+    }
 }
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+use thiserror::Error;
+
+use crate::stats::Stats;
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+pub struct PageResult {
+    #[serde(default)]
+    pub best_wpm: f64,
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default)]
+    pub best_accuracy: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LessonStatus {
+    NotStarted,
+    InProgress,
+    Completed,
+}
+
+impl LessonStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LessonStatus::NotStarted => "not started",
+            LessonStatus::InProgress => "in progress",
+            LessonStatus::Completed => "completed",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct LessonProgress {
+    #[serde(default)]
+    pub pages: BTreeMap<usize, PageResult>,
+    // All lesson pages were passed
+    #[serde(default)]
+    pub completed: bool,
+}
+
+// Student results, stored separately from config.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Progress {
+    #[serde(default)]
+    pub lessons: BTreeMap<String, LessonProgress>,
+}
+
+impl Progress {
+    pub fn load(path: PathBuf) -> Result<Self, Error> {
+        if !path.exists() {
+            return Ok(Progress::default());
+        }
+        let content = fs::read_to_string(path).map_err(|e| Error::Read(e.to_string()))?;
+        let progress: Progress =
+            serde_yaml::from_str(&content).map_err(|e| Error::Parse(e.to_string()))?;
+        Ok(progress)
+    }
+
+    pub async fn save(&self, path: PathBuf) -> Result<(), Error> {
+        let content = serde_yaml::to_string(self).map_err(|e| Error::Parse(e.to_string()))?;
+        tokio::fs::write(path, &content)
+            .await
+            .map_err(|e| Error::Write(e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn page(&self, lesson: &str, page: usize) -> Option<&PageResult> {
+        self.lessons
+            .get(lesson)
+            .and_then(|lesson| lesson.pages.get(&page))
+    }
+
+    pub fn completed(&self, lesson: &str) -> bool {
+        self.lessons
+            .get(lesson)
+            .is_some_and(|lesson| lesson.completed)
+    }
+
+    pub fn complete(&mut self, lesson: &str) {
+        self.lessons
+            .entry(lesson.to_string())
+            .or_default()
+            .completed = true;
+    }
+
+    pub fn status(&self, lesson: &str) -> LessonStatus {
+        match self.lessons.get(lesson) {
+            Some(progress) if progress.completed => LessonStatus::Completed,
+            Some(progress) if !progress.pages.is_empty() => LessonStatus::InProgress,
+            _ => LessonStatus::NotStarted,
+        }
+    }
+
+    // Best speed and best accuracy over lesson pages.
+    // They may be reached on different pages, so are shown separately.
+    pub fn best(&self, lesson: &str) -> Option<(f64, f64)> {
+        let pages = &self.lessons.get(lesson)?.pages;
+        if pages.is_empty() {
+            return None;
+        }
+        Some(pages.values().fold((0.0, 0.0), |(wpm, accuracy), page| {
+            (wpm.max(page.best_wpm), accuracy.max(page.best_accuracy))
+        }))
+    }
+
+    // Records exercise result of page.
+    // Returns true if speed beats previous best one.
+    pub fn record(&mut self, lesson: &str, page: usize, stats: &Stats) -> bool {
+        let result = self
+            .lessons
+            .entry(lesson.to_string())
+            .or_default()
+            .pages
+            .entry(page)
+            .or_default();
+        let new_record = result.attempts > 0 && stats.wpm > result.best_wpm;
+        if result.attempts == 0 || stats.wpm > result.best_wpm {
+            result.best_wpm = stats.wpm;
+        }
+        if result.attempts == 0 || stats.accuracy > result.best_accuracy {
+            result.best_accuracy = stats.accuracy;
+        }
+        result.attempts += 1;
+        new_record
+    }
+}
+
+#[derive(Debug, Error, Clone)]
+pub enum Error {
+    #[error("Progress could not be read: {0}")]
+    Read(String),
+    #[error("Progress could not be saved: {0}")]
+    Write(String),
+    #[error("{0}")]
+    Parse(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(wpm: f64, accuracy: f64) -> Stats {
+        Stats {
+            wpm,
+            accuracy,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn completed_lesson_is_remembered() {
+        let mut progress = Progress::default();
+        assert!(!progress.completed("l01"));
+        assert_eq!(progress.status("l01"), LessonStatus::NotStarted);
+        progress.record("l01", 0, &stats(20.0, 95.0));
+        assert!(!progress.completed("l01"));
+        assert_eq!(progress.status("l01"), LessonStatus::InProgress);
+        progress.complete("l01");
+        assert!(progress.completed("l01"));
+        assert_eq!(progress.status("l01"), LessonStatus::Completed);
+        assert!(!progress.completed("l02"));
+    }
+
+    #[test]
+    fn lesson_without_results_can_be_completed() {
+        let mut progress = Progress::default();
+        progress.complete("l01");
+        assert!(progress.completed("l01"));
+        assert_eq!(progress.best("l01"), None);
+    }
+
+    #[test]
+    fn best_results_are_kept_per_page() {
+        let mut progress = Progress::default();
+        assert!(!progress.record("l01", 1, &stats(20.0, 90.0)));
+        assert!(progress.record("l01", 1, &stats(25.0, 85.0)));
+        assert!(!progress.record("l01", 2, &stats(15.0, 98.0)));
+        let page = progress.page("l01", 1).unwrap();
+        assert_eq!(
+            (page.best_wpm, page.best_accuracy, page.attempts),
+            (25.0, 90.0, 2)
+        );
+        assert_eq!(progress.best("l01"), Some((25.0, 98.0)));
+    }
+}
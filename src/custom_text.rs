@@ -0,0 +1,245 @@
+use std::{collections::HashSet, fs, path::PathBuf};
+
+use thiserror::Error;
+
+use crate::{
+    blocks,
+    config::{parse_markdown, Block, Config, Exercise, Lesson, LessonPage},
+};
+
+// Custom sessions are recorded in progress under this prefix,
+// so they do not mix with lessons of index.
+pub const LESSON_PREFIX: &str = "custom/";
+pub const CLIPBOARD_NAME: &str = "clipboard";
+const EXTENSIONS: [&str; 2] = ["txt", "md"];
+
+// User texts are stored in texts folder of config directory
+pub fn texts_dir() -> PathBuf {
+    Config::config_dir().join("texts")
+}
+
+// File names of user texts. Missing texts folder means there are no texts.
+pub fn files() -> Result<Vec<String>, Error> {
+    let entries = match fs::read_dir(texts_dir()) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => return Err(Error::List(err.to_string())),
+    };
+    let mut files: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        })
+        .filter_map(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map(String::from)
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+pub fn read(file: &str) -> Result<String, Error> {
+    let content =
+        fs::read_to_string(texts_dir().join(file)).map_err(|e| Error::Read(e.to_string()))?;
+    if file.to_lowercase().ends_with(".md") {
+        Ok(strip_markdown(&content))
+    } else {
+        Ok(content)
+    }
+}
+
+// Removes markdown markup, which is not meant to be typed.
+// Blocks become paragraphs, list items included, pictures are dropped.
+fn strip_markdown(content: &str) -> String {
+    parse_markdown(content)
+        .into_iter()
+        .flat_map(|block| match block {
+            Block::Heading(text) | Block::Paragraph(text) | Block::Callout { text, .. } => {
+                vec![text]
+            }
+            Block::List(items) => items,
+            _ => vec![],
+        })
+        .map(|text| blocks::plain_text(&strip_links(&text)))
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+// Links [text](url) are replaced with their text, inline pictures are dropped
+fn strip_links(text: &str) -> String {
+    let mut stripped = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        let after_open = &rest[start + 1..];
+        let link = after_open.find(']').and_then(|close| {
+            let target = after_open[close + 1..].strip_prefix('(')?;
+            let end = target.find(')')?;
+            Some((&after_open[..close], &target[end + 1..]))
+        });
+        let Some((label, after)) = link else {
+            stripped.push_str(&rest[..start + 1]);
+            rest = after_open;
+            continue;
+        };
+        if rest[..start].ends_with('!') {
+            stripped.push_str(&rest[..start - 1]);
+        } else {
+            stripped.push_str(&rest[..start]);
+            stripped.push_str(label);
+        }
+        rest = after;
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+// Replaces typographic characters with ones found on keyboards and
+// removes characters, which can not be typed with active keyboard.
+// Empty lines are kept, as they separate paragraphs.
+pub fn normalize(text: &str, allowed: &HashSet<char>) -> String {
+    let mut normalized = String::new();
+    for character in text.chars() {
+        let replacement = match character {
+            '\n' => "\n",
+            '\r' => continue,
+            '‘' | '’' | '‚' | '′' => "'",
+            '“' | '”' | '„' | '″' | '«' | '»' => "\"",
+            '–' | '—' | '‐' | '−' => "-",
+            '…' => "...",
+            c if c.is_whitespace() => " ",
+            _ => "",
+        };
+        let candidates = if replacement.is_empty() {
+            character.to_string()
+        } else {
+            replacement.to_string()
+        };
+        if candidates
+            .chars()
+            .all(|c| c.is_whitespace() || allowed.contains(&c))
+        {
+            normalized.push_str(&candidates);
+        } else if let Some(lower) = candidates
+            .to_lowercase()
+            .chars()
+            .next()
+            .filter(|c| allowed.contains(c))
+        {
+            // Keyboard without Shift still can type text in lower case
+            normalized.push(lower);
+        }
+    }
+    let mut paragraphs: Vec<String> = vec![String::new()];
+    for line in normalized.lines() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let paragraph = paragraphs.last_mut().expect("paragraphs are never empty");
+        if words.is_empty() {
+            if !paragraph.is_empty() {
+                paragraphs.push(String::new());
+            }
+            continue;
+        }
+        if !paragraph.is_empty() {
+            paragraph.push(' ');
+        }
+        paragraph.push_str(&words.join(" "));
+    }
+    paragraphs.retain(|paragraph| !paragraph.is_empty());
+    paragraphs.join("\n\n")
+}
+
+// One page lesson for typing given text, finished with next page chord
+pub fn lesson(title: &str, text: &str, next_chord: &str) -> Lesson {
+    Lesson {
+        pages: vec![LessonPage {
+            title: title.to_string(),
+            content: "Type your text. Characters, which can not be typed \
+                      with current keyboard, are removed."
+                .to_string(),
+            keyboard: true,
+            exercises: vec![Exercise::Paragraph(text.to_string())],
+            content2: format!("Press <{}> when done", next_chord),
+            ..Default::default()
+        }],
+    }
+}
+
+#[derive(Debug, Error, Clone)]
+pub enum Error {
+    #[error("Custom text could not be read: {0}")]
+    Read(String),
+    #[error("Custom texts could not be listed: {0}")]
+    List(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowed(characters: &str) -> HashSet<char> {
+        characters.chars().collect()
+    }
+
+    const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz'\"-.";
+
+    #[test]
+    fn typographic_characters_are_replaced() {
+        let allowed = allowed(&format!("{}IT", LOWERCASE));
+        assert_eq!(
+            normalize("It’s “quoted” — done…", &allowed),
+            "It's \"quoted\" - done..."
+        );
+    }
+
+    #[test]
+    fn text_is_lowercased_without_shift() {
+        assert_eq!(normalize("Hello Wörld", &allowed(LOWERCASE)), "hello wrld");
+    }
+
+    #[test]
+    fn paragraph_breaks_are_kept() {
+        assert_eq!(
+            normalize("first\r\nline\n\n\n  second\n", &allowed(LOWERCASE)),
+            "first line\n\nsecond"
+        );
+    }
+
+    #[test]
+    fn markdown_markup_is_stripped() {
+        assert_eq!(
+            strip_markdown("# Title\n> **bold** and `code`\n\ntext"),
+            "Title\n\nbold and code\n\ntext"
+        );
+    }
+
+    #[test]
+    fn single_emphasis_markers_are_stripped() {
+        assert_eq!(
+            strip_markdown("*one* and _two_, 2*3 in snake_case"),
+            "one and two, 2*3 in snake_case"
+        );
+    }
+
+    #[test]
+    fn links_keep_text_and_pictures_are_dropped() {
+        assert_eq!(
+            strip_markdown("See [docs](http://a.b) and ![logo](logo.png) [here]"),
+            "See docs and  [here]"
+        );
+    }
+
+    #[test]
+    fn list_markers_are_stripped() {
+        assert_eq!(
+            strip_markdown("- first\n* second\n1. third"),
+            "first\n\nsecond\n\nthird"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::{env, path::PathBuf};
 
 pub const CONFIG_FILE_NAME: &str = "config.yaml";
+pub const PROGRESS_FILE_NAME: &str = "progress.yaml";
 
 pub fn config_dir() -> PathBuf {
     portable_dir().unwrap_or_else(platform_specific_config_dir)
@@ -1,15 +1,27 @@
+use std::collections::BTreeMap;
+
 use iced::{
-    widget::{column, text},
+    widget::{column, text, Row},
     Element, Event,
 };
 
-use crate::{TICK_MILIS, beeper::Beeper, font};
+use crate::{font, scale, sound::SoundEvent, TICK_MILIS};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Message {
     Tick,
     Event(Event),
     SetFocus(bool),
+    SetTextSize(f32),
+    SetSteadyCursor(bool),
+    SetLetterSpacing(f32),
+    // Spaces inserted by <Tab>
+    SetTabWidth(usize),
+    // Fill in leading indentation of exercise, when it gets focus
+    SetAutoIndent(bool),
+    // Text typed before, e.g. restored on resume. Ignored if it does
+    // not match exercise.
+    SetInput(String),
 }
 
 pub struct ExerciseComponent {
@@ -19,7 +31,15 @@ pub struct ExerciseComponent {
     focus: bool,
     pub errors: u64,
     pub mseconds: u64,
-    beeper: Beeper,
+    text_size: f32,
+    steady_cursor: bool,
+    letter_spacing: f32,
+    tab_width: usize,
+    auto_indent: bool,
+    // Characters filled in automatically, which are not counted in stats
+    pub prefilled: usize,
+    // Errors by expected character
+    pub char_errors: BTreeMap<char, u64>,
 }
 
 impl ExerciseComponent {
@@ -31,23 +51,47 @@ impl ExerciseComponent {
             focus: false,
             errors: 0,
             mseconds: 0,
-            beeper: Beeper::new(),
+            text_size: scale::EXERCISE_SIZE,
+            steady_cursor: false,
+            letter_spacing: 0.0,
+            tab_width: 2,
+            auto_indent: false,
+            prefilled: 0,
+            char_errors: BTreeMap::new(),
         }
     }
 
-    pub fn update(&mut self, message: Message) {
+    // Returns sound event to play, if any
+    pub fn update(&mut self, message: Message) -> Option<SoundEvent> {
         #![allow(unused)]
         match message {
             Message::Tick => {
-                self.mseconds += TICK_MILIS;
-                self.cursor_visible = !self.cursor_visible;
-                if !self.exercise.starts_with(&self.input) {
-                    self.beeper.play_beep();
+                // Time is counted only while student types this line
+                if self.focus && self.typing() && !self.exercise_finished() {
+                    self.mseconds += TICK_MILIS;
+                }
+                self.cursor_visible = self.steady_cursor || !self.cursor_visible;
+                let error = !self.exercise.starts_with(&self.input);
+                if error {
+                    // Expected characters from first wrong one till end of input
+                    let typed = self.input.chars().count();
+                    let correct = self
+                        .exercise
+                        .chars()
+                        .zip(self.input.chars())
+                        .take_while(|(expected, typed)| expected == typed)
+                        .count();
+                    for expected in self.exercise.chars().skip(correct).take(typed - correct) {
+                        *self.char_errors.entry(expected).or_default() += 1;
+                    }
                 }
                 while !self.exercise.starts_with(&self.input) && !self.input.is_empty() {
                     self.errors += 1;
                     self.input.pop();
                 }
+                if error {
+                    return Some(SoundEvent::Error);
+                }
             }
             Message::Event(event) => {
                 if let Event::Keyboard(iced::keyboard::Event::KeyPressed {
@@ -60,22 +104,31 @@ impl ExerciseComponent {
                 }) = event
                 {
                     if !self.focus {
-                        return;
+                        return None;
                     }
                     // println!("Key pressed: {:?}. Location: {:?}", key, location);
                     if let Some(ch) = text {
                         match key {
                             iced::keyboard::Key::Character(_) => {
                                 self.input.push_str(ch.as_str());
+                                return Some(SoundEvent::KeyPress);
                             }
                             iced::keyboard::Key::Named(iced::keyboard::key::Named::Backspace) => {
                                 self.input.pop();
                             }
                             iced::keyboard::Key::Named(iced::keyboard::key::Named::Space) => {
                                 self.input.push(' ');
+                                return Some(SoundEvent::KeyPress);
                             }
                             iced::keyboard::Key::Named(iced::keyboard::key::Named::Tab) => {
-                                self.input.push_str("  ");
+                                let expected =
+                                    self.exercise.chars().nth(self.input.chars().count());
+                                if expected == Some('\t') {
+                                    self.input.push('\t');
+                                } else {
+                                    self.input.push_str(&" ".repeat(self.tab_width));
+                                }
+                                return Some(SoundEvent::KeyPress);
                             }
                             _ => {}
                         }
@@ -84,25 +137,144 @@ impl ExerciseComponent {
             }
             Message::SetFocus(focus) => {
                 self.focus = focus;
+                if focus && self.auto_indent && self.input.is_empty() {
+                    let indent: String = self
+                        .exercise
+                        .chars()
+                        .take_while(|ch| ch.is_whitespace())
+                        .collect();
+                    self.prefilled = indent.chars().count();
+                    self.input = indent;
+                }
+            }
+            Message::SetTextSize(size) => {
+                self.text_size = size;
+            }
+            Message::SetSteadyCursor(steady) => {
+                self.steady_cursor = steady;
+                self.cursor_visible = steady;
+            }
+            Message::SetLetterSpacing(spacing) => {
+                self.letter_spacing = spacing;
+            }
+            Message::SetTabWidth(width) => {
+                self.tab_width = width;
+            }
+            Message::SetAutoIndent(auto_indent) => {
+                self.auto_indent = auto_indent;
+            }
+            Message::SetInput(input) => {
+                // Time and errors of restored input are not known,
+                // so it is not counted in stats like auto indent
+                if self.exercise.starts_with(&input) {
+                    self.prefilled = input.chars().count();
+                    self.input = input;
+                }
             }
         }
+        None
     }
 
     pub fn view(&self) -> Element<'_, Message> {
-        let ex = text(&self.exercise).size(20).font(font::MONO.clone());
         let done = if self.cursor_visible && (self.focus || self.exercise.is_empty()) {
-            text(format!("{}_", self.input))
-                .size(20)
-                .font(font::MONO.clone())
+            format!("{}_", self.input)
         } else {
-            text(format!("{} ", self.input))
-                .size(20)
-                .font(font::MONO.clone())
+            format!("{} ", self.input)
         };
-        column![ex, done].padding(10).into()
+        column![self.line(&self.exercise), self.line(&done)]
+            .padding(self.text_size / 2.0)
+            .into()
+    }
+
+    // Text line of exercise. With letter spacing every letter is separate
+    // text, so exercise and input letters stay aligned.
+    fn line(&self, content: &str) -> Element<'_, Message> {
+        if self.letter_spacing > 0.0 {
+            Row::with_children(content.chars().map(|ch| {
+                text(ch.to_string())
+                    .size(self.text_size)
+                    .font(font::MONO.clone())
+                    .into()
+            }))
+            .spacing(self.letter_spacing)
+            .into()
+        } else {
+            text(content.to_string())
+                .size(self.text_size)
+                .font(font::MONO.clone())
+                .into()
+        }
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    pub fn focused(&self) -> bool {
+        self.focus
+    }
+
+    // True if student has typed anything in this exercise
+    pub fn started(&self) -> bool {
+        !self.input.is_empty() || self.errors > 0
     }
 
     pub fn exercise_finished(&self) -> bool {
         self.input.eq(&self.exercise)
     }
+
+    // True if student has typed something after prefilled text
+    fn typing(&self) -> bool {
+        self.input.chars().count() > self.prefilled || self.errors > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticks(ex: &mut ExerciseComponent, count: u64) {
+        for _ in 0..count {
+            ex.update(Message::Tick);
+        }
+    }
+
+    #[test]
+    fn time_is_counted_from_first_keystroke() {
+        let mut ex = ExerciseComponent::new("asdf");
+        ex.update(Message::SetFocus(true));
+        ticks(&mut ex, 3);
+        assert_eq!(ex.mseconds, 0);
+        ex.input.push('a');
+        ticks(&mut ex, 2);
+        assert_eq!(ex.mseconds, 2 * TICK_MILIS);
+    }
+
+    #[test]
+    fn time_is_not_counted_on_unfocused_line() {
+        let mut ex = ExerciseComponent::new("asdf");
+        ex.input.push('a');
+        ticks(&mut ex, 2);
+        assert_eq!(ex.mseconds, 0);
+    }
+
+    #[test]
+    fn time_is_not_counted_on_finished_line() {
+        let mut ex = ExerciseComponent::new("asdf");
+        ex.update(Message::SetFocus(true));
+        ex.input.push_str("as");
+        ticks(&mut ex, 1);
+        ex.input.push_str("df");
+        ticks(&mut ex, 3);
+        assert_eq!(ex.mseconds, TICK_MILIS);
+    }
+
+    #[test]
+    fn restored_input_does_not_start_time() {
+        let mut ex = ExerciseComponent::new("asdf");
+        ex.update(Message::SetInput("as".to_string()));
+        ex.update(Message::SetFocus(true));
+        ticks(&mut ex, 2);
+        assert_eq!(ex.mseconds, 0);
+    }
 }
@@ -1,10 +1,19 @@
 use std::borrow::Cow;
 use std::sync::OnceLock;
+use std::{fs, path::PathBuf};
 
 use iced::font;
 
+use crate::config::Config;
+
+pub const DEFAULT_FAMILY: &str = "Iosevka Term";
+// OpenDyslexic is not embedded, it should be installed in system
+// or put into user fonts folder.
+pub const DYSLEXIA_FAMILY: &str = "OpenDyslexicMono";
+
 pub static MONO: Font = Font::new(false);
 pub static MONO_BOLD: Font = Font::new(true);
+pub static UI: Font = Font::new(false);
 
 #[derive(Debug, Clone)]
 pub struct Font {
@@ -21,17 +30,20 @@ impl Font {
     }
 
     fn set(&self, name: String) {
-        let name = Box::leak(name.into_boxed_str());
         let weight = if self.bold {
             font::Weight::Bold
         } else {
             font::Weight::Normal
         };
+        // Empty name means iced default font
+        let font = if name.is_empty() {
+            iced::Font::DEFAULT
+        } else {
+            let name = Box::leak(name.into_boxed_str());
+            iced::Font::with_name(name)
+        };
 
-        let _ = self.inner.set(iced::Font {
-            weight,
-            ..iced::Font::with_name(name)
-        });
+        let _ = self.inner.set(iced::Font { weight, ..font });
     }
 }
 
@@ -41,15 +53,67 @@ impl From<Font> for iced::Font {
     }
 }
 
-pub fn set() {
-    let family = String::from("Iosevka Term");
+// Fonts can be changed only at startup, as iced default font
+// is part of application settings.
+pub fn set(exercise_family: &str, ui_family: &str) {
+    let family = if exercise_family.is_empty() {
+        String::from(DEFAULT_FAMILY)
+    } else {
+        String::from(exercise_family)
+    };
 
     MONO.set(family.clone());
     MONO_BOLD.set(family);
+    UI.set(String::from(ui_family));
+}
+
+// User supplied fonts are stored in fonts folder of config directory.
+// Apart from selecting them in settings, they act as fallback fonts:
+// characters missing in selected font (Cyrillic, Greek etc.) are
+// taken from any loaded font, which has them.
+pub fn user_fonts_dir() -> PathBuf {
+    Config::config_dir().join("fonts")
+}
+
+fn user_font_files() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(user_fonts_dir()) else {
+        return vec![];
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| matches!(ext.to_lowercase().as_str(), "ttf" | "otf"))
+                .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+    files
 }
 
-pub fn load() -> Vec<Cow<'static, [u8]>> {
-    vec![
+// Font families available for selection - embedded, user supplied and system fonts.
+pub fn families() -> Vec<String> {
+    let mut db = fontdb::Database::new();
+    db.load_system_fonts();
+    // Unreadable files are reported by load at startup
+    for file in user_font_files() {
+        let _ = db.load_font_file(&file);
+    }
+
+    let mut families: Vec<String> = db
+        .faces()
+        .flat_map(|face| face.families.iter().map(|(name, _)| name.clone()))
+        .collect();
+    families.push(String::from(DEFAULT_FAMILY));
+    families.sort();
+    families.dedup();
+    families
+}
+
+// Embedded and user supplied fonts, with errors of files, which could not be read
+pub fn load() -> (Vec<Cow<'static, [u8]>>, Vec<String>) {
+    let mut fonts: Vec<Cow<'static, [u8]>> = vec![
         include_bytes!("../fonts/iosevka-term-regular.ttf")
             .as_slice()
             .into(),
@@ -59,5 +123,13 @@ pub fn load() -> Vec<Cow<'static, [u8]>> {
         include_bytes!("../fonts/iosevka-term-italic.ttf")
             .as_slice()
             .into(),
-    ]
+    ];
+    let mut errors = vec![];
+    for file in user_font_files() {
+        match fs::read(&file) {
+            Ok(bytes) => fonts.push(bytes.into()),
+            Err(err) => errors.push(format!("Font {} failed to read: {}", file.display(), err)),
+        }
+    }
+    (fonts, errors)
 }
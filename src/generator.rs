@@ -0,0 +1,201 @@
+use std::{
+    collections::HashSet,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+// Random exercise content. With seed content is the same every time,
+// otherwise it is new on every exercise start.
+fn rng(seed: Option<u64>) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed.unwrap_or_else(new_seed))
+}
+
+// Seed for exercise content, which is new on every exercise start
+pub fn new_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+// Shuffles lines and takes pick of them (all if pick not set)
+pub fn shuffle_lines(lines: &str, pick: Option<usize>, seed: Option<u64>) -> Vec<String> {
+    let mut lines: Vec<String> = lines
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(String::from)
+        .collect();
+    lines.shuffle(&mut rng(seed));
+    if let Some(pick) = pick {
+        lines.truncate(pick);
+    }
+    lines
+}
+
+// Builds lines of randomly chosen words
+pub fn sample_words(
+    words: &[&str],
+    lines: usize,
+    words_per_line: usize,
+    seed: Option<u64>,
+) -> Vec<String> {
+    if words.is_empty() {
+        return vec![];
+    }
+    let mut rng = rng(seed);
+    (0..lines)
+        .map(|_| {
+            (0..words_per_line)
+                .filter_map(|_| words.choose(&mut rng).copied())
+                .collect::<Vec<&str>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+// Words of word list or any text. Punctuation around words is dropped.
+pub fn words_from_text(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| word.trim_matches(|ch: char| !ch.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+// Keeps words, which can be typed with allowed characters. Words starting
+// with capital letter are lowercased, if capitals can not be typed yet.
+pub fn filter_words(
+    words: Vec<String>,
+    allowed: Option<&HashSet<char>>,
+    max_length: Option<usize>,
+) -> Vec<String> {
+    words
+        .into_iter()
+        .filter(|word| max_length.is_none_or(|max| word.chars().count() <= max))
+        .filter_map(|word| {
+            let Some(allowed) = allowed else {
+                return Some(word);
+            };
+            let typeable = |word: &str| word.chars().all(|ch| allowed.contains(&ch));
+            if typeable(&word) {
+                Some(word)
+            } else {
+                let lowercase = word.to_lowercase();
+                typeable(&lowercase).then_some(lowercase)
+            }
+        })
+        .collect()
+}
+
+// Wraps text to lines of at most columns characters. Wrapping space stays
+// at the end of line, so it is typed as in continuous text. Single line breaks
+// are joined as spaces, empty lines end paragraphs.
+pub fn wrap_text(text: &str, columns: usize) -> Vec<String> {
+    // Room for at least one character and wrapping space
+    let columns = columns.max(2);
+    let mut lines = vec![];
+    for paragraph in text.split("\n\n") {
+        let words: Vec<&str> = paragraph.split_whitespace().collect();
+        let mut line = String::new();
+        for (index, word) in words.iter().enumerate() {
+            let last = index + 1 == words.len();
+            let separator = usize::from(!last);
+            let mut word = word.to_string();
+            // Words not fitting line with their wrapping space are split
+            while word.chars().count() + separator > columns {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                let part = columns.min(word.chars().count() - 1);
+                let rest = word.split_off(word.char_indices().nth(part).map_or(0, |(i, _)| i));
+                lines.push(word);
+                word = rest;
+            }
+            if !line.is_empty() && line.chars().count() + word.chars().count() + separator > columns
+            {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push_str(&word);
+            if !last {
+                line.push(' ');
+            }
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINES: &str = "one\ntwo\n\nthree\nfour\nfive";
+
+    #[test]
+    fn same_seed_shuffles_lines_same_way() {
+        let lines = shuffle_lines(LINES, None, Some(7));
+        assert_eq!(lines, shuffle_lines(LINES, None, Some(7)));
+        let mut sorted = lines.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec!["five", "four", "one", "three", "two"]);
+    }
+
+    #[test]
+    fn pick_takes_first_shuffled_lines() {
+        let lines = shuffle_lines(LINES, None, Some(7));
+        assert_eq!(shuffle_lines(LINES, Some(2), Some(7)), lines[..2]);
+        assert_eq!(shuffle_lines(LINES, Some(10), Some(7)), lines);
+    }
+
+    #[test]
+    fn same_seed_samples_same_words() {
+        let words = ["ask", "sad", "lad", "fall", "dad"];
+        let lines = sample_words(&words, 3, 4, Some(42));
+        assert_eq!(lines, sample_words(&words, 3, 4, Some(42)));
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.split(' ').count() == 4
+            && line.split(' ').all(|word| words.contains(&word))));
+        assert!(sample_words(&[], 3, 4, Some(42)).is_empty());
+    }
+
+    #[test]
+    fn wrapping_space_stays_at_line_end() {
+        assert_eq!(
+            wrap_text("one two three", 8),
+            vec!["one two ".to_string(), "three".to_string()]
+        );
+    }
+
+    #[test]
+    fn wrapping_space_fits_in_line() {
+        let lines = wrap_text("ab cd ef", 5);
+        assert_eq!(lines, vec!["ab ".to_string(), "cd ef".to_string()]);
+        let lines = wrap_text("abcd ef", 4);
+        assert_eq!(lines, vec!["abc".to_string(), "d ef".to_string()]);
+    }
+
+    #[test]
+    fn words_longer_than_line_are_split() {
+        assert_eq!(
+            wrap_text("ab abcdefghij", 4),
+            vec![
+                "ab ".to_string(),
+                "abcd".to_string(),
+                "efgh".to_string(),
+                "ij".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_line_ends_paragraph() {
+        assert_eq!(
+            wrap_text("first line\nsecond\n\nnext", 20),
+            vec!["first line second".to_string(), "next".to_string()]
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use iced::{
+    widget::canvas::{self, LineCap, Path, Stroke},
+    Color, Point, Rectangle, Size,
+};
+
+use crate::keyboard_config::{Key, KeyboardConfig, Location, PressedKeyCoord};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Finger {
+    LeftPinky,
+    LeftRing,
+    LeftMiddle,
+    LeftIndex,
+    LeftThumb,
+    RightThumb,
+    RightIndex,
+    RightMiddle,
+    RightRing,
+    RightPinky,
+}
+
+// Home keys of fingers. Index fingers also cover inner columns (G and H),
+// but rest on first key listed for them.
+const HOME_KEYS: [(Finger, &str); 10] = [
+    (Finger::LeftPinky, "a"),
+    (Finger::LeftRing, "s"),
+    (Finger::LeftMiddle, "d"),
+    (Finger::LeftIndex, "f"),
+    (Finger::LeftIndex, "g"),
+    (Finger::RightIndex, "j"),
+    (Finger::RightIndex, "h"),
+    (Finger::RightMiddle, "k"),
+    (Finger::RightRing, "l"),
+    (Finger::RightPinky, ";"),
+];
+
+pub struct HandColors {
+    pub hand: Color,
+    pub finger: Color,
+}
+
+// Key bounds of drawn keyboard
+pub type KeyBounds = Vec<(PressedKeyCoord, Rectangle)>;
+
+// Draws two stylised hands resting on home row. Finger, which types
+// moving key, is drawn on that key, fingers for hint keys are highlighted.
+pub fn draw(
+    frame: &mut canvas::Frame,
+    config: &KeyboardConfig,
+    bounds: &KeyBounds,
+    key_width: f32,
+    hints: &[PressedKeyCoord],
+    moving: Option<&PressedKeyCoord>,
+    colors: &HandColors,
+) {
+    let center = |coord: &PressedKeyCoord| {
+        bounds
+            .iter()
+            .find(|(key, _)| key == coord)
+            .map(|(_, rect)| rect.center())
+    };
+    let mut homes = vec![];
+    for (finger, character) in HOME_KEYS {
+        // Hands are not drawn for keyboards without usual home row
+        let Some(home) = config
+            .find_spec(&Key::Character(character.to_string()))
+            .and_then(|coord| center(&coord))
+        else {
+            return;
+        };
+        homes.push((finger, home));
+    }
+    let home = |finger: Finger| {
+        homes
+            .iter()
+            .find(|(home_finger, _)| *home_finger == finger)
+            .map(|(_, point)| *point)
+            .expect("all fingers have home key")
+    };
+    let palm_top = home(Finger::LeftIndex).y + key_width * 1.2;
+    let space = config
+        .find_spec(&Key::Named("Space".to_string()))
+        .and_then(|coord| center(&coord));
+    let thumb_y = space.map_or(palm_top + key_width, |space| space.y);
+    let thumbs = [
+        (Finger::LeftThumb, home(Finger::LeftIndex).x + key_width),
+        (Finger::RightThumb, home(Finger::RightIndex).x - key_width),
+    ];
+
+    let finger_of = |coord: &PressedKeyCoord| -> Option<Finger> {
+        let spec = config.rows.get(coord.row)?.keys.get(coord.key)?;
+        if matches!(spec.location, Location::Numpad) {
+            return None;
+        }
+        let target = center(coord)?;
+        if matches!(&spec.key, Key::Named(name) if name == "Space") {
+            return Some(Finger::RightThumb);
+        }
+        homes
+            .iter()
+            .min_by(|(_, a), (_, b)| (a.x - target.x).abs().total_cmp(&(b.x - target.x).abs()))
+            .map(|(finger, _)| *finger)
+    };
+    let moving = moving.and_then(|coord| Some((finger_of(coord)?, center(coord)?)));
+    let hinted: Vec<Finger> = hints.iter().filter_map(finger_of).collect();
+
+    for (left, right) in [
+        (Finger::LeftPinky, Finger::LeftIndex),
+        (Finger::RightIndex, Finger::RightPinky),
+    ] {
+        let from = home(left).x - key_width * 0.5;
+        let to = home(right).x + key_width * 0.5;
+        let palm = Path::rounded_rectangle(
+            Point::new(from, palm_top),
+            Size::new(to - from, key_width * 1.6),
+            (key_width * 0.5).into(),
+        );
+        frame.fill(&palm, colors.hand);
+    }
+
+    let fingers = homes
+        .iter()
+        // Index fingers are drawn once, on their rest keys
+        .filter(|(finger, point)| *point == home(*finger))
+        .map(|(finger, point)| (*finger, Point::new(point.x, palm_top), *point))
+        .chain(thumbs.iter().map(|(finger, x)| {
+            (
+                *finger,
+                Point::new(*x, palm_top + key_width * 0.6),
+                Point::new(*x, thumb_y),
+            )
+        }));
+    for (finger, base, rest) in fingers {
+        let (tip, color) = match moving {
+            Some((moving_finger, target)) if moving_finger == finger => (target, colors.finger),
+            _ if hinted.contains(&finger) => (rest, colors.finger),
+            _ => (rest, colors.hand),
+        };
+        let stroke = Stroke::default()
+            .with_color(color)
+            .with_width(key_width * 0.5)
+            .with_line_cap(LineCap::Round);
+        frame.stroke(&Path::line(base, tip), stroke);
+    }
+}
@@ -3,10 +3,12 @@ use iced::{
     border::Radius,
     mouse,
     widget::canvas::{Cache, Geometry, Path, Text},
-    Color, Element, Event, Length, Point, Rectangle, Renderer, Size, Theme,
+    Color, Element, Event, Length, Pixels, Point, Rectangle, Renderer, Size, Theme,
 };
 
+use crate::hands::{self, HandColors, KeyBounds};
 use crate::keyboard_config::{KeyboardConfig, PressedKeyCoord};
+use crate::scale;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Message {
@@ -14,6 +16,13 @@ pub enum Message {
     Tick,
     ClearKeys,
     SetShowKeys(Vec<PressedKeyCoord>),
+    // Keys of shortcut, highlighted together
+    SetChordKeys(Vec<PressedKeyCoord>),
+    SetScale(f32),
+    SetStaticHints(bool),
+    SetHighContrast(bool),
+    // Show hands on home row, moving finger to key being shown
+    SetHands(bool),
 }
 
 #[derive(Default)]
@@ -22,14 +31,66 @@ pub struct KeyboardComponent {
     config: KeyboardConfig,
     pressed_keys: Vec<PressedKeyCoord>,
     show_keys: Vec<PressedKeyCoord>,
+    chord_keys: Vec<PressedKeyCoord>,
     key_to_show: usize,
     hide: bool,
+    scale: f32,
+    // Show keys are highlighted all the time instead of flashing
+    static_hints: bool,
+    high_contrast: bool,
+    hands: bool,
+}
+
+struct Palette {
+    keyboard: Color,
+    letter: Color,
+    key_fill: Color,
+    key_press_letter: Color,
+    key_press_fill: Color,
+    key_hint_letter: Color,
+    key_hint_fill: Color,
+    hands: HandColors,
+}
+
+impl Palette {
+    fn new(high_contrast: bool) -> Self {
+        if high_contrast {
+            Palette {
+                keyboard: Color::BLACK,
+                letter: Color::WHITE,
+                key_fill: Color::from_rgb8(0x20, 0x20, 0x20),
+                key_press_letter: Color::BLACK,
+                key_press_fill: Color::from_rgb8(0xFF, 0xD7, 0x00),
+                key_hint_letter: Color::BLACK,
+                key_hint_fill: Color::from_rgb8(0x00, 0xE5, 0xFF),
+                hands: HandColors {
+                    hand: Color::from_rgba8(0xFF, 0xFF, 0xFF, 0.35),
+                    finger: Color::from_rgba8(0xFF, 0xD7, 0x00, 0.8),
+                },
+            }
+        } else {
+            Palette {
+                keyboard: Color::from_rgb8(0xFF, 0xFF, 0xFF),
+                letter: Color::BLACK,
+                key_fill: Color::from_rgb8(0xD1, 0xD1, 0xD1),
+                key_press_letter: Color::from_rgb8(0xFF, 0xFF, 0xFF),
+                key_press_fill: Color::from_rgb8(0x91, 0x91, 0x91),
+                key_hint_letter: Color::BLACK,
+                key_hint_fill: Color::from_rgb8(0xA8, 0xD8, 0xFF),
+                hands: HandColors {
+                    hand: Color::from_rgba8(0xE0, 0xAC, 0x69, 0.35),
+                    finger: Color::from_rgba8(0x2E, 0x7D, 0x32, 0.6),
+                },
+            }
+        }
+    }
 }
 
 impl KeyboardComponent {
     pub fn new(config: KeyboardConfig) -> KeyboardComponent {
         KeyboardComponent {
             config,
+            scale: scale::DEFAULT,
             ..Default::default()
         }
     }
@@ -70,9 +131,14 @@ impl KeyboardComponent {
             }
             Message::SetShowKeys(keys) => {
                 self.show_keys = keys;
+                self.draw_cache.clear();
+            }
+            Message::SetChordKeys(keys) => {
+                self.chord_keys = keys;
+                self.draw_cache.clear();
             }
             Message::Tick => {
-                if !self.show_keys.is_empty() {
+                if !self.show_keys.is_empty() && !self.static_hints {
                     if let Some(key) = self.show_keys.get(self.key_to_show) {
                         if self.hide {
                             self.pressed_keys
@@ -89,8 +155,33 @@ impl KeyboardComponent {
                     self.draw_cache.clear();
                 }
             }
+            Message::SetScale(scale) => {
+                self.scale = scale;
+                self.draw_cache.clear();
+            }
+            Message::SetStaticHints(static_hints) => {
+                if static_hints != self.static_hints {
+                    // Drop keys left from flashing
+                    self.pressed_keys.clear();
+                    self.key_to_show = 0;
+                    self.hide = false;
+                    self.static_hints = static_hints;
+                    self.draw_cache.clear();
+                }
+            }
+            Message::SetHighContrast(high_contrast) => {
+                self.high_contrast = high_contrast;
+                self.draw_cache.clear();
+            }
+            Message::SetHands(hands) => {
+                self.hands = hands;
+                self.draw_cache.clear();
+            }
             Message::ClearKeys => {
                 self.show_keys.clear();
+                self.chord_keys.clear();
+                self.hands = false;
+                self.draw_cache.clear();
                 self.pressed_keys.clear();
                 self.key_to_show = 0;
                 self.hide = false;
@@ -98,6 +189,14 @@ impl KeyboardComponent {
         }
     }
 
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    pub fn config(&self) -> &KeyboardConfig {
+        &self.config
+    }
+
     pub fn view(&self) -> Element<'_, Message> {
         canvas(self as &Self)
             .width(Length::Fill)
@@ -117,17 +216,18 @@ impl<Message> canvas::Program<Message> for KeyboardComponent {
         bounds: Rectangle,
         _cursor: mouse::Cursor,
     ) -> Vec<Geometry> {
-        let letter_color = Color::BLACK;
-        let key_fill_color = Color::from_rgb8(0xD1, 0xD1, 0xD1);
-        let key_press_letter_color = Color::from_rgb8(0xFF, 0xFF, 0xFF);
-        let key_press_fill_color = Color::from_rgb8(0x91, 0x91, 0x91);
-        let second_label_y: f32 = 28.0;
+        let palette = Palette::new(self.high_contrast);
 
         let keyboard = self.draw_cache.draw(renderer, bounds.size(), |frame| {
             let keyboard_width = frame.width();
             let simple_key_width = keyboard_width / self.config.cols_for_keys;
             let keyboard_height = simple_key_width * 7.0;
             let keyboard_top_pad = (frame.height() - keyboard_height) / 2.0;
+            // Labels follow key size, so they stay inside keys at any window size.
+            // Two labels must fit in one key, so scale is limited by key height.
+            let label_size = (simple_key_width * scale::KEY_LABEL_RATIO * self.scale)
+                .min((simple_key_width - self.config.key_text_top_pad * 2.0) / 2.0)
+                .max(1.0);
 
             let keyboard = Path::rounded_rectangle(
                 Point {
@@ -140,25 +240,48 @@ impl<Message> canvas::Program<Message> for KeyboardComponent {
                 },
                 Radius::from(self.config.keyboard_corner_curve),
             );
-            frame.fill(&keyboard, Color::from_rgb8(0xFF, 0xFF, 0xFF));
+            frame.fill(&keyboard, palette.keyboard);
 
+            let mut key_bounds: KeyBounds = vec![];
             let mut key_y: f32 = keyboard_top_pad + self.config.keyboard_side_padding;
             for (row_index, row) in self.config.rows.iter().enumerate() {
                 let mut key_x: f32 = self.config.keyboard_side_padding;
                 for (key_index, keyspec) in row.keys.iter().enumerate() {
-                    let mut cur_letter_color = letter_color;
-                    let mut cur_fill_color = key_fill_color;
+                    if let Some(column) = keyspec.column {
+                        key_x = self.config.keyboard_side_padding
+                            + column * (simple_key_width + self.config.keyboard_side_padding);
+                    }
+                    let key_height = simple_key_width * keyspec.height_ratio
+                        + self.config.space_between_keys * (keyspec.height_ratio - 1.0);
+                    let mut cur_letter_color = palette.letter;
+                    let mut cur_fill_color = palette.key_fill;
+                    let is_key =
+                        |key: &PressedKeyCoord| key.row == row_index && key.key == key_index;
+                    if (self.static_hints && self.show_keys.iter().any(is_key))
+                        || self.chord_keys.iter().any(is_key)
+                    {
+                        cur_letter_color = palette.key_hint_letter;
+                        cur_fill_color = palette.key_hint_fill;
+                    }
                     for pressed_key in self.pressed_keys.iter() {
                         if pressed_key.row == row_index && pressed_key.key == key_index {
-                            cur_letter_color = key_press_letter_color;
-                            cur_fill_color = key_press_fill_color;
+                            cur_letter_color = palette.key_press_letter;
+                            cur_fill_color = palette.key_press_fill;
                         }
                     }
 
                     let key_pos = Point::new(key_x, key_y);
+                    let key_size = Size::new(simple_key_width * keyspec.width_ratio, key_height);
+                    key_bounds.push((
+                        PressedKeyCoord {
+                            row: row_index,
+                            key: key_index,
+                        },
+                        Rectangle::new(key_pos, key_size),
+                    ));
                     let key = Path::rounded_rectangle(
                         key_pos,
-                        Size::new(simple_key_width * keyspec.width_ratio, simple_key_width),
+                        key_size,
                         Radius::from(self.config.keyboard_corner_curve),
                     );
                     frame.fill(&key, cur_fill_color);
@@ -169,6 +292,7 @@ impl<Message> canvas::Program<Message> for KeyboardComponent {
                             key_y + self.config.key_text_top_pad,
                         ),
                         color: cur_letter_color,
+                        size: Pixels(label_size),
                         ..canvas::Text::default()
                     });
                     if !keyspec.label2.is_empty() {
@@ -176,9 +300,10 @@ impl<Message> canvas::Program<Message> for KeyboardComponent {
                             content: keyspec.label2.clone(),
                             position: Point::new(
                                 key_x + self.config.key_text_left_pad,
-                                key_y + self.config.key_text_top_pad + second_label_y,
+                                key_y + key_height - self.config.key_text_top_pad - label_size,
                             ),
                             color: cur_letter_color,
+                            size: Pixels(label_size),
                             ..canvas::Text::default()
                         });
                     }
@@ -188,7 +313,46 @@ impl<Message> canvas::Program<Message> for KeyboardComponent {
                 }
                 key_y = key_y + simple_key_width + self.config.space_between_keys;
             }
+
+            if self.hands {
+                // Flashing key is shown while hide flag is set
+                let moving = if self.static_hints || !self.hide {
+                    None
+                } else {
+                    self.show_keys.get(self.key_to_show)
+                };
+                let hints: &[PressedKeyCoord] = if self.static_hints {
+                    &self.show_keys
+                } else {
+                    &[]
+                };
+                hands::draw(
+                    frame,
+                    &self.config,
+                    &key_bounds,
+                    simple_key_width,
+                    hints,
+                    moving,
+                    &palette.hands,
+                );
+            }
         });
         vec![keyboard]
     }
 }
+
+// State of keyboard checked by tests of application, which drives it
+#[cfg(test)]
+pub(crate) mod test_helpers {
+    use super::*;
+
+    impl KeyboardComponent {
+        pub(crate) fn show_keys(&self) -> &[PressedKeyCoord] {
+            &self.show_keys
+        }
+
+        pub(crate) fn hands(&self) -> bool {
+            self.hands
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use serde::Deserialize;
-use std::{fs, path::PathBuf};
+use std::{collections::HashSet, fs, path::PathBuf};
 use thiserror::Error;
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
 pub enum Key {
     /// A key with an established name.
     Named(String),
@@ -45,6 +45,13 @@ pub struct KeySpec {
     // Should be specified if key is larger than usual keys
     #[serde(default = "default_width_ratio")]
     pub width_ratio: f32,
+    // Key height ratio, e.g. 2.0 for numpad <+> and <Enter> spanning two rows
+    #[serde(default = "default_width_ratio")]
+    pub height_ratio: f32,
+    // Key position from keyboard left side in key widths.
+    // Used to align blocks like numpad, otherwise key follows previous one.
+    #[serde(default)]
+    pub column: Option<f32>,
     pub label1: String,
     #[serde(default)]
     pub label2: String,
@@ -65,24 +72,17 @@ impl KeySpec {
                 if let Key::Named(my_name) = &self.key {
                     let name_str = format!("{:?}", name);
                     if name_str.eq(my_name) {
-                        match self.location {
-                            Location::Left => {
-                                if location == iced::keyboard::Location::Left {
-                                    return true;
-                                }
-                            }
-                            Location::Right => {
-                                if location == iced::keyboard::Location::Right {
-                                    return true;
-                                }
-                            }
-                            _ => {}
-                        }
+                        return self.location_eq(location);
                     }
                 };
                 false
             }
             iced::keyboard::Key::Character(character) => {
+                // Digits and operators exist both in main block and numpad
+                let numpad = matches!(self.location, Location::Numpad);
+                if numpad != (location == iced::keyboard::Location::Numpad) {
+                    return false;
+                }
                 if let Key::Character(my_name) = &self.key {
                     let name_with_quotes = format!(r#""{}""#, my_name);
                     let name_str = format!("{:?}", character);
@@ -95,6 +95,17 @@ impl KeySpec {
             iced::keyboard::Key::Unidentified => false,
         }
     }
+
+    // Named keys, like Enter or Escape, come with Standard location,
+    // modifiers come with Left or Right one.
+    fn location_eq(&self, location: iced::keyboard::Location) -> bool {
+        match self.location {
+            Location::Standard => location == iced::keyboard::Location::Standard,
+            Location::Left => location == iced::keyboard::Location::Left,
+            Location::Right => location == iced::keyboard::Location::Right,
+            Location::Numpad => location == iced::keyboard::Location::Numpad,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -104,6 +115,8 @@ pub struct Row {
 
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct KeyboardConfig {
+    #[serde(default)]
+    pub name: String,
     pub cols_for_keys: f32,
     pub space_between_keys: f32,
     pub keyboard_corner_curve: f32,
@@ -135,6 +148,63 @@ impl KeyboardConfig {
         }
         None
     }
+
+    // First key with given key value, left modifiers come before right ones
+    pub fn find_spec(&self, key: &Key) -> Option<PressedKeyCoord> {
+        self.rows.iter().enumerate().find_map(|(row, keys)| {
+            keys.keys
+                .iter()
+                .position(|spec| spec.key == *key)
+                .map(|key| PressedKeyCoord { row, key })
+        })
+    }
+
+    fn key_spec(&self, coord: &PressedKeyCoord) -> Option<&KeySpec> {
+        self.rows
+            .get(coord.row)
+            .and_then(|row| row.keys.get(coord.key))
+    }
+
+    pub fn all_keys(&self) -> Vec<PressedKeyCoord> {
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row, keys)| {
+                (0..keys.keys.len()).map(move |key| PressedKeyCoord { row, key })
+            })
+            .collect()
+    }
+
+    pub fn has_numpad(&self) -> bool {
+        self.rows
+            .iter()
+            .flat_map(|row| row.keys.iter())
+            .any(|spec| matches!(spec.location, Location::Numpad))
+    }
+
+    // Characters, which can be typed using given keys.
+    // Shifted characters are included only if Shift key is among keys.
+    pub fn typeable_chars(&self, keys: &[PressedKeyCoord]) -> HashSet<char> {
+        let specs: Vec<&KeySpec> = keys.iter().filter_map(|key| self.key_spec(key)).collect();
+        let shift = specs
+            .iter()
+            .any(|spec| matches!(&spec.key, Key::Named(name) if name == "Shift"));
+        let mut chars = HashSet::from([' ']);
+        for spec in specs {
+            let Key::Character(character) = &spec.key else {
+                continue;
+            };
+            chars.extend(character.chars());
+            if shift {
+                chars.extend(character.to_uppercase().chars());
+                // Upper label shows shifted symbol, e.g. ! on 1 key
+                if spec.label1.chars().count() == 1 {
+                    chars.extend(spec.label1.chars());
+                }
+            }
+        }
+        chars
+    }
 }
 
 #[derive(Debug, Error, Clone)]
@@ -1,45 +1,79 @@
 pub type Result<T> = core::result::Result<T, Error>;
 pub type Error = Box<dyn std::error::Error>;
 
+use std::{collections::HashSet, sync::Arc};
+
 use config::{Config, IndexRecord};
 use exercise_component::ExerciseComponent;
 use keyboard_component::KeyboardComponent;
+use metronome::Metronome;
+use repetition::Repetition;
+use settings_component::SettingsComponent;
+use shortcut_component::{Chord, ShortcutComponent};
+use sound::{SoundEvent, SoundTheme};
+use stats::Stats;
+use template::{PageContext, PageRenderer};
 
-use handlebars::Handlebars;
 use iced::{
     event,
-    keyboard::{key, Modifiers},
     widget::{
-        self, button, canvas::path::lyon_path::geom::euclid::num::Round, column, container, text,
+        self, button, canvas::path::lyon_path::geom::euclid::num::Round, column, container,
+        scrollable, text, Column,
     },
     window, Element, Event, Length, Subscription, Task,
 };
-use serde_json::json;
 
-use crate::{config::Lesson, keyboard_config::KeyboardConfig};
+use crate::{
+    beeper::Beeper,
+    config::{Action, Block, GateMode, KeyFilter, Lesson},
+    keyboard_config::{KeyboardConfig, PressedKeyCoord},
+};
 
+mod beeper;
+mod blocks;
 mod config;
+mod custom_text;
 mod environment;
-mod beeper;
 mod exercise_component;
 mod font;
+mod generator;
+mod hands;
 mod keyboard_component;
 mod keyboard_config;
+mod metronome;
+mod repetition;
+mod scale;
+mod settings_component;
+mod shortcut_component;
+mod sound;
+mod stats;
+mod template;
 
 pub const TICK_MILIS: u64 = 500;
+// Iced default window width, used until first resize event
+const DEFAULT_WINDOW_WIDTH: f32 = 1024.0;
+// Wrapped text lines shown at once in paragraph exercises
+const PARAGRAPH_VISIBLE_LINES: usize = 6;
 
 fn main() -> iced::Result {
-    font::set();
+    let mut config = Config::load().expect("Error loading context");
+    let (exercise_font, ui_font) = config.fonts();
+    font::set(exercise_font, ui_font);
+    let (fonts, font_errors) = font::load();
+    config.load_errors.extend(font_errors);
 
     iced::application("Raiti - Touch typing tutor", Raiti::update, Raiti::view)
         .subscription(Raiti::subscription)
+        // Window close is handled to save lesson input
+        .exit_on_close_request(false)
         .settings(iced::Settings {
             id: None,
             antialiasing: false,
-            fonts: font::load(),
+            fonts,
+            default_font: font::UI.clone().into(),
             ..Default::default()
         })
-        .run_with(Raiti::new)
+        .run_with(move || Raiti::new(config))
 }
 
 #[derive(Default, PartialEq, Eq, Debug, Clone)]
@@ -48,6 +82,16 @@ pub enum DialogType {
     None,
     ConfirmExitLesson,
     ConfirmExitApp,
+    Settings,
+    // Exercise results did not meet page thresholds
+    ExerciseFailed,
+    // List of lesson pages to jump to
+    PageList,
+    Paused,
+    // Results of last exercise
+    Stats,
+    // Key bindings
+    Help,
 }
 
 #[derive(Default)]
@@ -55,27 +99,71 @@ struct Raiti {
     config: Config,
     lesson: Option<Lesson>,
     exercise_components: Vec<ExerciseComponent>,
-    was_errors: u64,
-    was_wpm: f64,
+    // Results of last exercise finished in current lesson
+    was: Option<Stats>,
+    // Page, which results are in was
+    was_page: usize,
+    renderer: PageRenderer,
+    metronome: Option<Metronome>,
+    repetition: Option<Repetition>,
+    shortcuts: Option<ShortcutComponent>,
+    // Text of paragraph exercise, which lines are wrapped to window width
+    paragraph: Option<String>,
+    window_width: f32,
+    // Pages visited in current lesson, for going back
+    page_history: Vec<usize>,
+    // Student skipped pages, so lesson is not marked completed
+    pages_skipped: bool,
+    // Pages, which pass thresholds were met in current lesson
+    passed_pages: HashSet<usize>,
+    // Keys introduced by lessons before current one, read when lesson is started
+    earlier_keys: Vec<PressedKeyCoord>,
+    // Seed of random exercises without own seed, saved with typed input
+    page_seed: u64,
+    // Problem to show to student, e.g. file which could not be read
+    notice: Option<String>,
+    // File names of user texts, listed in lesson picker
+    custom_texts: Vec<String>,
     keyboard: KeyboardComponent,
+    keyboard_hidden: bool,
     dialog: DialogType,
+    settings: SettingsComponent,
+    beeper: Beeper,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Event(Event),
     Tick,
+    Beat,
     Exercise(exercise_component::Message),
+    Shortcut(shortcut_component::Message),
     Keyboard(keyboard_component::Message),
     LessonSelected(IndexRecord),
+    // Continue current lesson from saved page
+    ResumeLesson,
+    CustomTextSelected(String),
+    RefreshCustomTexts,
+    PasteCustomText,
+    CustomTextPasted(Option<String>),
     Confirm(DialogType),
+    OpenSettings,
+    RepeatExercise,
+    ContinueLesson,
+    PreviousPage,
+    // Skip page without recording results
+    NextPage,
+    RestartPage,
+    RestartLesson,
+    OpenPageList,
+    JumpToPage(usize),
+    Settings(settings_component::Message),
     WindowSettingsSaved(core::result::Result<(), config::Error>),
 }
 
 impl Raiti {
-    fn new() -> (Self, Task<Message>) {
-        // Read config & initialize state
-        let config = Config::load().expect("Error loading context");
+    fn new(config: Config) -> (Self, Task<Message>) {
+        // Initialize state from config
         let keyboard_config = KeyboardConfig::load(
             Config::data_dir()
                 .join("keyboards")
@@ -83,29 +171,60 @@ impl Raiti {
         )
         .expect("Error loading keyboard config");
 
-        let lesson = if !config.current_lesson.is_empty() {
-            Some(
-                Lesson::load(Config::data_dir().join(format!("{}.yaml", config.current_lesson)))
-                    .expect("Error loading lesson"),
-            )
+        // Saved lesson, which can not be loaded, is reported in picker
+        let (lesson, notice) = if config.current_lesson.is_empty() {
+            (None, None)
         } else {
-            None
+            match Lesson::load(Config::data_dir().join(format!("{}.yaml", config.current_lesson))) {
+                Ok(lesson) => (Some(lesson), None),
+                Err(err) => (
+                    None,
+                    Some(format!(
+                        "Lesson {} could not be loaded: {}",
+                        config.current_lesson, err
+                    )),
+                ),
+            }
         };
 
         let mut raiti = Self {
+            beeper: Beeper::new(
+                Arc::new(SoundTheme::load(&config.sound)),
+                config.sound.output_volume(),
+            ),
             config: config.clone(),
-            lesson,
             exercise_components: vec![],
             keyboard: KeyboardComponent::new(keyboard_config),
+            settings: SettingsComponent::new(),
+            window_width: DEFAULT_WINDOW_WIDTH,
             ..Default::default()
         };
-
-        raiti.construct_exercise_components();
+        raiti.set_lesson(lesson);
+        let notices: Vec<String> = notice
+            .into_iter()
+            .chain(raiti.config.load_errors.iter().cloned())
+            .collect();
+        if !notices.is_empty() {
+            raiti.notice = Some(notices.join("\n"));
+        }
+        raiti.refresh_custom_texts();
+        raiti.apply_settings();
 
         (raiti, widget::focus_next())
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
+        let task = self.handle(message);
+        // Time of shortcut exercise does not run while dialog is shown
+        if let Some(shortcuts) = self.shortcuts.as_mut() {
+            shortcuts.update(shortcut_component::Message::SetPaused(
+                self.dialog != DialogType::None,
+            ));
+        }
+        task
+    }
+
+    fn handle(&mut self, message: Message) -> Task<Message> {
         #![allow(unused)]
         match message {
             Message::Exercise(message) => {
@@ -114,98 +233,273 @@ impl Raiti {
                 }
                 Task::none()
             }
+            Message::Shortcut(message) => {
+                if let Some(shortcuts) = self.shortcuts.as_mut() {
+                    shortcuts.update(message);
+                }
+                Task::none()
+            }
             Message::Event(event) => {
-                for exercise_component in self.exercise_components.iter_mut() {
-                    exercise_component.update(exercise_component::Message::Event(event.clone()));
+                match event {
+                    Event::Window(window::Event::Resized(size)) => {
+                        self.window_width = size.width;
+                        self.rewrap_paragraph();
+                    }
+                    Event::Window(window::Event::CloseRequested) => {
+                        return self.exit_with_save();
+                    }
+                    _ => {}
+                }
+                if self.shortcut_event(&event) {
+                    return Task::none();
                 }
-                self.keyboard
-                    .update(keyboard_component::Message::Event(event.clone()));
                 if let Event::Keyboard(iced::keyboard::Event::KeyPressed {
-                    key,
-                    location,
+                    key: iced::keyboard::Key::Character(ref c),
                     modifiers,
-                    text,
-                    modified_key,
-                    physical_key,
+                    ..
                 }) = event
                 {
-                    match key {
-                        iced::keyboard::Key::Named(key::Named::ArrowDown) => {
-                            if modifiers.contains(Modifiers::SHIFT)
-                                && modifiers.contains(Modifiers::ALT)
-                            {
-                                self.move_next_page();
-                            }
-                        }
-                        iced::keyboard::Key::Named(key::Named::Enter) => {
-                            if self.dialog == DialogType::ConfirmExitApp {
-                                return self.exit_with_save();
-                            }
-                            if self.dialog == DialogType::ConfirmExitLesson {
-                                self.dialog = DialogType::None;
-                                self.lesson = None;
-                                return Task::none();
-                            }
-                            let finished = self
-                                .exercise_components
-                                .iter()
-                                .all(|ex| ex.exercise_finished());
-                            if finished {
-                                self.move_next_page();
-                            }
-                            for exercise_component in self.exercise_components.iter_mut() {
-                                if !exercise_component.exercise_finished() {
-                                    exercise_component
-                                        .update(exercise_component::Message::SetFocus(true));
-                                    break;
-                                } else {
-                                    exercise_component
-                                        .update(exercise_component::Message::SetFocus(false));
-                                }
-                            }
-                        }
-                        iced::keyboard::Key::Named(key::Named::Escape) => {
-                            if self.dialog == DialogType::None {
-                                if self.lesson.is_some() {
-                                    self.dialog = DialogType::ConfirmExitLesson;
-                                } else {
-                                    self.dialog = DialogType::ConfirmExitApp;
-                                }
-                            } else {
-                                self.dialog = DialogType::None;
-                            }
+                    // Ctrl (Cmd on macOS) with +/- changes UI scale, Ctrl+0 resets it.
+                    // Such key presses should not get into exercise input.
+                    if modifiers.command() {
+                        let ui_scale = match c.as_str() {
+                            "+" | "=" => Some(scale::increase(self.config.ui_scale)),
+                            "-" | "_" => Some(scale::decrease(self.config.ui_scale)),
+                            "0" => Some(scale::DEFAULT),
+                            _ => None,
+                        };
+                        if let Some(ui_scale) = ui_scale {
+                            self.config.ui_scale = ui_scale;
+                            self.apply_settings();
+                            self.rewrap_paragraph();
+                            return Task::none();
                         }
-                        _ => {}
                     }
                 }
+                if let Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                    ref key,
+                    modifiers,
+                    ..
+                }) = event
+                {
+                    // Enter ends exercise line, whatever key next page is bound to
+                    if *key == iced::keyboard::Key::Named(iced::keyboard::key::Named::Enter)
+                        && modifiers.is_empty()
+                        && self.dialog == DialogType::None
+                        && !self.exercise_components.is_empty()
+                        && !self.exercises_finished()
+                    {
+                        self.focus_next_exercise();
+                        self.keyboard
+                            .update(keyboard_component::Message::Event(event.clone()));
+                        return Task::none();
+                    }
+                    // Continue binding works only in failed exercise dialog,
+                    // otherwise key is typed in exercise
+                    if let Some(action) =
+                        self.config
+                            .keybindings
+                            .action(key, modifiers)
+                            .filter(|action| {
+                                *action != Action::Continue
+                                    || self.dialog == DialogType::ExerciseFailed
+                            })
+                    {
+                        self.keyboard
+                            .update(keyboard_component::Message::Event(event.clone()));
+                        return self.perform(action);
+                    }
+                }
+                let was_finished = self.exercises_finished();
+                let mut sounds = vec![];
+                // Typing goes to exercises only while no dialog is shown
+                if self.dialog == DialogType::None {
+                    for exercise_component in self.exercise_components.iter_mut() {
+                        sounds.extend(
+                            exercise_component
+                                .update(exercise_component::Message::Event(event.clone())),
+                        );
+                    }
+                }
+                if sounds.contains(&SoundEvent::KeyPress) {
+                    if let Some(metronome) = self.metronome.as_mut() {
+                        metronome.key_pressed();
+                    }
+                }
+                self.play_all(sounds);
+                // Paragraph lines flow into each other without <Enter>
+                if self.paragraph.is_some() {
+                    self.focus_next_exercise();
+                }
+                if !was_finished && self.exercises_finished() {
+                    self.play(SoundEvent::ExerciseComplete);
+                }
+                self.keyboard
+                    .update(keyboard_component::Message::Event(event.clone()));
                 Task::none()
             }
             Message::Tick => {
-                for exercise_component in self.exercise_components.iter_mut() {
-                    exercise_component.update(exercise_component::Message::Tick);
+                let mut sounds = vec![];
+                // Exercise time does not run while dialog is shown
+                if self.dialog == DialogType::None {
+                    for exercise_component in self.exercise_components.iter_mut() {
+                        sounds.extend(exercise_component.update(exercise_component::Message::Tick));
+                    }
                 }
+                self.play_all(sounds);
 
                 self.keyboard.update(keyboard_component::Message::Tick);
                 Task::none()
             }
+            Message::Beat => {
+                if let Some(metronome) = self.metronome.as_mut() {
+                    metronome.beat();
+                    self.play(SoundEvent::Metronome);
+                }
+                Task::none()
+            }
             Message::Keyboard(message) => {
                 self.keyboard.update(message);
                 Task::none()
             }
-            Message::LessonSelected(lesson) => {
-                self.exercise_components.clear();
-                // TODO: find a way to fail lesson load without unwrap
-                self.lesson = Some(
-                    self.config
-                        .load_lesson(&lesson.file)
-                        .expect("Error loading lesson on selection"),
-                );
+            Message::LessonSelected(record) => {
+                // Broken lesson is reported and student stays in picker
+                match self.config.load_lesson(&record.file) {
+                    Ok(lesson) => self.set_lesson(Some(lesson)),
+                    Err(err) => {
+                        self.notice = Some(format!(
+                            "Lesson {} could not be loaded: {}",
+                            record.title, err
+                        ))
+                    }
+                }
+                Task::none()
+            }
+            Message::ResumeLesson => {
+                let (page, exercise) = (self.config.current_page, self.config.current_exercise);
+                let file = self.config.current_lesson.clone();
+                match self.config.load_lesson(&file) {
+                    Ok(lesson) => {
+                        if page < lesson.pages.len() {
+                            self.config.current_page = page;
+                            self.config.current_exercise = exercise;
+                        }
+                        self.set_lesson(Some(lesson));
+                    }
+                    Err(err) => {
+                        self.notice = Some(format!("Lesson {} could not be loaded: {}", file, err))
+                    }
+                }
+                Task::none()
+            }
+            Message::CustomTextSelected(file) => {
+                match custom_text::read(&file) {
+                    Ok(content) => self.start_custom_lesson(&file, &content),
+                    Err(err) => self.notice = Some(err.to_string()),
+                }
+                Task::none()
+            }
+            Message::RefreshCustomTexts => {
+                self.refresh_custom_texts();
+                Task::none()
+            }
+            Message::PasteCustomText => iced::clipboard::read().map(Message::CustomTextPasted),
+            Message::CustomTextPasted(content) => {
+                if let Some(content) = content {
+                    self.start_custom_lesson(custom_text::CLIPBOARD_NAME, &content);
+                }
+                Task::none()
+            }
+            Message::RepeatExercise => {
+                self.repeat_exercise();
+                Task::none()
+            }
+            Message::ContinueLesson => {
+                self.dialog = DialogType::None;
+                self.move_next_page();
+                Task::none()
+            }
+            Message::PreviousPage => {
+                self.dialog = DialogType::None;
+                // Results of later page must not drive branches of earlier one
+                self.was = None;
+                let page = self
+                    .page_history
+                    .pop()
+                    .unwrap_or(self.config.current_page.saturating_sub(1));
+                self.go_to_page(page);
+                Task::none()
+            }
+            Message::NextPage => {
+                if !self.can_skip_to(self.config.current_page + 1) {
+                    return Task::none();
+                }
+                self.dialog = DialogType::None;
+                self.pages_skipped = true;
+                // Results of earlier page must not drive branches and templates
+                self.was = None;
+                self.move_next_page();
+                Task::none()
+            }
+            Message::RestartPage => {
+                self.dialog = DialogType::None;
+                self.enter_page();
+                Task::none()
+            }
+            Message::RestartLesson => {
+                self.dialog = DialogType::None;
+                self.page_history.clear();
+                self.pages_skipped = false;
+                self.go_to_page(0);
+                Task::none()
+            }
+            Message::OpenPageList => {
+                self.dialog = DialogType::PageList;
+                Task::none()
+            }
+            Message::JumpToPage(page) => {
+                if !self.can_skip_to(page) {
+                    return Task::none();
+                }
+                self.dialog = DialogType::None;
+                if page > self.config.current_page {
+                    self.pages_skipped = true;
+                }
+                self.was = None;
+                self.page_history.push(self.config.current_page);
+                self.go_to_page(page);
+                Task::none()
+            }
+            Message::OpenSettings => {
+                self.settings.load_fonts();
+                self.dialog = DialogType::Settings;
+                Task::none()
+            }
+            Message::Settings(message) => {
+                let sound = self.config.sound.clone();
+                self.settings.update(message, &mut self.config);
+                // Decoding sounds is slow, so theme is reloaded only when
+                // sounds change, not on every volume slider step
+                if sound.pack != self.config.sound.pack || sound.events != self.config.sound.events
+                {
+                    self.beeper
+                        .set_theme(Arc::new(SoundTheme::load(&self.config.sound)));
+                }
+                self.beeper.set_volume(self.config.sound.output_volume());
+                self.apply_settings();
+                self.rewrap_paragraph();
                 Task::none()
             }
             Message::Confirm(dialog_type) => match dialog_type {
-                DialogType::None => Task::none(),
+                DialogType::None
+                | DialogType::Settings
+                | DialogType::ExerciseFailed
+                | DialogType::PageList
+                | DialogType::Paused
+                | DialogType::Stats
+                | DialogType::Help => Task::none(),
                 DialogType::ConfirmExitLesson => {
-                    self.lesson = None;
+                    self.set_lesson(None);
                     self.dialog = DialogType::None;
                     Task::none()
                 }
@@ -223,10 +517,169 @@ impl Raiti {
     fn view(&self) -> Element<'_, Message> {
         match self.dialog {
             DialogType::None => {}
+            DialogType::ExerciseFailed => {
+                let text_size = self.config.text_size(scale::TEXT_SIZE);
+                let fail_content = self
+                    .lesson
+                    .as_ref()
+                    .and_then(|lesson| lesson.get_page(self.config.current_page))
+                    .map(|page| {
+                        self.renderer.render(
+                            self.config.current_page,
+                            "fail_content",
+                            &page.fail_content,
+                            &self.page_context(),
+                        )
+                    })
+                    .unwrap_or_default();
+                let mut content = column![
+                    text("Exercise not passed").size(self.config.text_size(scale::TITLE_SIZE)),
+                    text(fail_content).size(text_size),
+                    button(
+                        text(format!(
+                            "Repeat exercise <{}>",
+                            self.config.keybindings.chord(Action::Next)
+                        ))
+                        .size(text_size)
+                    )
+                    .padding([10, 20])
+                    .on_press(Message::RepeatExercise),
+                ]
+                .spacing(10);
+                if self.config.gates == GateMode::Advisory {
+                    content = content.push(
+                        button(
+                            text(format!(
+                                "Continue anyway <{}>",
+                                self.config.keybindings.chord(Action::Continue)
+                            ))
+                            .size(text_size),
+                        )
+                        .padding([10, 20])
+                        .on_press(Message::ContinueLesson),
+                    );
+                }
+                return container(content)
+                    .padding(30)
+                    .center_x(Length::Fill)
+                    .center_y(Length::Fill)
+                    .into();
+            }
+            DialogType::Paused => {
+                let content = column![
+                    text("Paused").size(self.config.text_size(scale::TITLE_SIZE)),
+                    text(format!(
+                        "Press {} to continue",
+                        self.config.keybindings.chord(Action::Pause)
+                    ))
+                    .size(self.config.text_size(scale::TEXT_SIZE)),
+                ]
+                .spacing(10);
+                return container(content)
+                    .padding(30)
+                    .center_x(Length::Fill)
+                    .center_y(Length::Fill)
+                    .into();
+            }
+            DialogType::Stats => {
+                let text_size = self.config.text_size(scale::TEXT_SIZE);
+                let mut content =
+                    column![text("Last exercise").size(self.config.text_size(scale::TITLE_SIZE))]
+                        .spacing(10);
+                match &self.was {
+                    Some(was) => {
+                        for line in [
+                            format!("Speed: {} wpm ({} gross)", was.wpm, was.gross_wpm),
+                            format!("Accuracy: {}%", was.accuracy),
+                            format!("Errors: {}", was.errors),
+                            format!("Duration: {} s", was.duration),
+                        ] {
+                            content = content.push(text(line).size(text_size));
+                        }
+                    }
+                    None => {
+                        content = content.push(text("No exercise finished yet").size(text_size));
+                    }
+                }
+                if let Some((wpm, accuracy)) =
+                    self.config.progress.best(&self.config.current_lesson)
+                {
+                    content = content.push(
+                        text(format!(
+                            "Lesson best speed: {} wpm, best accuracy: {}%",
+                            wpm, accuracy
+                        ))
+                        .size(text_size),
+                    );
+                }
+                return container(content)
+                    .padding(30)
+                    .center_x(Length::Fill)
+                    .center_y(Length::Fill)
+                    .into();
+            }
+            DialogType::Help => {
+                let text_size = self.config.text_size(scale::TEXT_SIZE);
+                let mut content =
+                    column![text("Key bindings").size(self.config.text_size(scale::TITLE_SIZE))]
+                        .spacing(10);
+                for (action, chord) in self.config.keybindings.bindings() {
+                    content = content
+                        .push(text(format!("{} - {}", chord, action.label())).size(text_size));
+                }
+                content = content
+                    .push(text("Key bindings can be changed in config.yaml").size(text_size));
+                return container(scrollable(content))
+                    .padding(30)
+                    .center_x(Length::Fill)
+                    .center_y(Length::Fill)
+                    .into();
+            }
+            DialogType::PageList => {
+                let text_size = self.config.text_size(scale::TEXT_SIZE);
+                let context = self.page_context();
+                let mut list =
+                    column![text("Go to page").size(self.config.text_size(scale::TITLE_SIZE))]
+                        .spacing(10);
+                if let Some(lesson) = &self.lesson {
+                    for (index, page) in lesson.pages.iter().enumerate() {
+                        let title = self.renderer.render(index, "title", &page.title, &context);
+                        let marker = if index == self.config.current_page {
+                            "> "
+                        } else {
+                            ""
+                        };
+                        list = list.push(
+                            button(
+                                text(format!("{}{}. {}", marker, index + 1, title)).size(text_size),
+                            )
+                            .on_press_maybe(
+                                self.can_skip_to(index)
+                                    .then_some(Message::JumpToPage(index)),
+                            ),
+                        );
+                    }
+                }
+                return container(scrollable(list))
+                    .padding(30)
+                    .center_x(Length::Fill)
+                    .center_y(Length::Fill)
+                    .into();
+            }
+            DialogType::Settings => {
+                return container(scrollable(
+                    self.settings.view(&self.config).map(Message::Settings),
+                ))
+                .padding(30)
+                .center_x(Length::Fill)
+                .center_y(Length::Fill)
+                .into();
+            }
             DialogType::ConfirmExitLesson => {
+                let text_size = self.config.text_size(scale::TEXT_SIZE);
                 let content = column![
-                    "Are you sure you want to exit lesson?",
-                    button("Yes, exit lesson")
+                    text("Are you sure you want to exit lesson?").size(text_size),
+                    button(text("Yes, exit lesson").size(text_size))
                         .padding([10, 20])
                         .on_press(Message::Confirm(DialogType::ConfirmExitLesson)),
                 ]
@@ -238,9 +691,10 @@ impl Raiti {
                     .into();
             }
             DialogType::ConfirmExitApp => {
+                let text_size = self.config.text_size(scale::TEXT_SIZE);
                 let content = column![
-                    "Are you sure you want to exit app?",
-                    button("Yes, exit app")
+                    text("Are you sure you want to exit app?").size(text_size),
+                    button(text("Yes, exit app").size(text_size))
                         .padding([10, 20])
                         .on_press(Message::Confirm(DialogType::ConfirmExitApp)),
                 ]
@@ -256,26 +710,66 @@ impl Raiti {
             let page = lesson
                 .get_page(self.config.current_page)
                 .expect("No page found at view");
-            let title = text(&page.title).size(25);
+            let context = self.page_context();
+            let current_page = self.config.current_page;
+            let render =
+                |field: &str, raw: &str| self.renderer.render(current_page, field, raw, &context);
+            let title =
+                text(render("title", &page.title)).size(self.config.text_size(scale::TITLE_SIZE));
             let mut page_content = column![title];
-            let reg = Handlebars::new();
-            let rendered_content = reg
-                .render_template(
-                    &page.content,
-                    &json!({"wpm": self.was_wpm, "errors": self.was_errors}),
-                )
-                .unwrap();
-            page_content = page_content.push(text(rendered_content.clone()));
-            page_content = if page.keyboard {
-                page_content.push(self.keyboard.view().map(Message::Keyboard))
+            if let Some(notice) = &self.notice {
+                page_content =
+                    page_content.push(text(notice).size(self.config.text_size(scale::TEXT_SIZE)));
+            }
+            if page.blocks.is_empty() {
+                page_content = page_content.push(
+                    text(render("content", &page.content))
+                        .size(self.config.text_size(scale::TEXT_SIZE)),
+                );
+                if !page.images.is_empty() {
+                    let height = self.config.text_size(scale::IMAGE_HEIGHT);
+                    let images = page.images.iter().map(|file| blocks::image(file, height));
+                    page_content =
+                        page_content.push(widget::Row::with_children(images).spacing(15));
+                }
+                if page.keyboard && !self.keyboard_hidden {
+                    page_content = page_content.push(self.keyboard.view().map(Message::Keyboard));
+                }
+                page_content = self.push_exercises_view(page_content);
             } else {
-                page_content
-            };
-
-            for exercise_component in self.exercise_components.iter() {
-                page_content = page_content.push(exercise_component.view().map(Message::Exercise));
+                for (index, block) in page.blocks.iter().enumerate() {
+                    page_content = match block {
+                        Block::Keyboard if self.keyboard_hidden => page_content,
+                        Block::Keyboard => {
+                            page_content.push(self.keyboard.view().map(Message::Keyboard))
+                        }
+                        Block::Exercises => self.push_exercises_view(page_content),
+                        block => {
+                            let texts = block
+                                .texts()
+                                .into_iter()
+                                .enumerate()
+                                .map(|(i, raw)| render(&format!("block{}.{}", index, i), raw));
+                            page_content.push(blocks::view(&block.with_texts(texts), &self.config))
+                        }
+                    };
+                }
+                // Exercises are shown at the end, if page does not place them
+                if !page.blocks.contains(&Block::Exercises) {
+                    page_content = self.push_exercises_view(page_content);
+                }
             }
-            page_content = page_content.push(text(&page.content2));
+            page_content = page_content.push(
+                text(render("content2", &page.content2))
+                    .size(self.config.text_size(scale::TEXT_SIZE)),
+            );
+            page_content = page_content.push(
+                text(format!(
+                    "{} - key bindings",
+                    self.config.keybindings.chord(Action::Help)
+                ))
+                .size(self.config.text_size(scale::HINT_SIZE)),
+            );
 
             container(page_content)
                 .padding(30)
@@ -283,14 +777,98 @@ impl Raiti {
                 .center_y(Length::Fill)
                 .into()
         } else {
-            let title = text("Please choose next lesson");
+            let text_size = self.config.text_size(scale::TEXT_SIZE);
+            let title = text("Please choose next lesson").size(text_size);
             let mut list = column![title].spacing(15);
+            if let Some(notice) = &self.notice {
+                list = list.push(text(notice).size(text_size));
+            }
+            if let Some(record) = self.config.resumable_lesson() {
+                list = list.push(
+                    button(
+                        text(format!(
+                            "Continue {}, page {}",
+                            record.title,
+                            self.config.current_page + 1
+                        ))
+                        .size(text_size),
+                    )
+                    .on_press(Message::ResumeLesson),
+                );
+            }
+            let mut section = "";
             for index_record in &self.config.index.lessons {
-                let btn = button(text(&index_record.title))
-                    .on_press(Message::LessonSelected(index_record.clone()));
+                if index_record.section != section {
+                    section = &index_record.section;
+                    list = list.push(text(section).size(self.config.text_size(scale::TITLE_SIZE)));
+                }
+                let mut label = index_record.title.clone();
+                if let Some(minutes) = index_record.minutes {
+                    label.push_str(&format!(" ({} min)", minutes));
+                }
+                label.push_str(&format!(
+                    " - {}",
+                    self.config.lesson_status(&index_record.file).label()
+                ));
+                if let Some((wpm, accuracy)) = self.config.progress.best(&index_record.file) {
+                    label.push_str(&format!(
+                        ", best speed {} wpm, best accuracy {}%",
+                        wpm, accuracy
+                    ));
+                }
+                let missing = self
+                    .config
+                    .missing_prerequisites(&index_record.file, |record| {
+                        self.keyboard_supports(record)
+                    });
+                let supported = self.keyboard_supports(index_record);
+                // Locked lesson button is disabled
+                let btn = button(text(label).size(text_size)).on_press_maybe(
+                    (missing.is_empty() && supported)
+                        .then(|| Message::LessonSelected(index_record.clone())),
+                );
+                let mut lesson_info = column![btn].spacing(5);
+                if !index_record.description.is_empty() {
+                    lesson_info = lesson_info.push(text(&index_record.description).size(text_size));
+                }
+                if !supported {
+                    lesson_info = lesson_info
+                        .push(text("Needs keyboard with numeric keypad").size(text_size));
+                }
+                if !missing.is_empty() {
+                    let titles: Vec<&str> =
+                        missing.iter().map(|record| record.title.as_str()).collect();
+                    lesson_info = lesson_info.push(
+                        text(format!("Complete first: {}", titles.join(", "))).size(text_size),
+                    );
+                }
+                list = list.push(lesson_info);
+            }
+            list = list.push(text("Practice custom text").size(text_size));
+            for file in &self.custom_texts {
+                let btn = button(text(file).size(text_size))
+                    .on_press(Message::CustomTextSelected(file.clone()));
                 list = list.push(btn);
             }
-            container(list)
+            list = list.push(
+                button(text("Paste text from clipboard").size(text_size))
+                    .on_press(Message::PasteCustomText),
+            );
+            list = list.push(
+                text(format!(
+                    "Put .txt or .md files in {} to practice them",
+                    custom_text::texts_dir().display()
+                ))
+                .size(text_size),
+            );
+            list = list.push(
+                button(text("Refresh custom texts").size(text_size))
+                    .on_press(Message::RefreshCustomTexts),
+            );
+            list =
+                list.push(button(text("Settings").size(text_size)).on_press(Message::OpenSettings));
+            list = list.push(text("Press Ctrl + / Ctrl - to change text size").size(text_size));
+            container(scrollable(list))
                 .padding(30)
                 .center_x(Length::Fill)
                 .center_y(Length::Fill)
@@ -298,84 +876,1021 @@ impl Raiti {
         }
     }
 
+    // Repetition results, code language, shortcuts and exercise lines of page
+    fn push_exercises_view<'a>(
+        &'a self,
+        mut page_content: Column<'a, Message>,
+    ) -> Column<'a, Message> {
+        let text_size = self.config.text_size(scale::TEXT_SIZE);
+        if let Some(repetition) = &self.repetition {
+            page_content = page_content.push(text(repetition.status()).size(text_size));
+            for (index, stats) in repetition.results.iter().enumerate() {
+                page_content = page_content.push(
+                    text(format!(
+                        "{}. {} wpm, {}% accuracy, {} errors",
+                        index + 1,
+                        stats.wpm,
+                        stats.accuracy,
+                        stats.errors
+                    ))
+                    .size(text_size),
+                );
+            }
+        }
+        if let Some(config::Exercise::Code { language, .. }) = self
+            .lesson
+            .as_ref()
+            .and_then(|lesson| {
+                lesson.get_exercise(self.config.current_page, self.config.current_exercise)
+            })
+            .map(config::Exercise::typed)
+        {
+            if !language.is_empty() {
+                page_content = page_content.push(text(language).size(text_size));
+            }
+        }
+        // Paragraph shows only lines around the one being typed
+        let (skip, take) = if self.paragraph.is_some() {
+            let focused = self
+                .exercise_components
+                .iter()
+                .position(|ex| ex.focused())
+                .unwrap_or_default();
+            (focused.saturating_sub(1), PARAGRAPH_VISIBLE_LINES)
+        } else {
+            (0, self.exercise_components.len())
+        };
+        if let Some(shortcuts) = &self.shortcuts {
+            page_content = page_content.push(shortcuts.view().map(Message::Shortcut));
+        }
+        for exercise_component in self.exercise_components.iter().skip(skip).take(take) {
+            page_content = page_content.push(exercise_component.view().map(Message::Exercise));
+        }
+        page_content
+    }
+
     fn subscription(&self) -> Subscription<Message> {
+        let beat = match &self.metronome {
+            Some(metronome)
+                if self.lesson.is_some()
+                    && self.dialog == DialogType::None
+                    && self
+                        .exercise_components
+                        .iter()
+                        .any(|ex| !ex.exercise_finished()) =>
+            {
+                iced::time::every(metronome.interval).map(|_| Message::Beat)
+            }
+            _ => Subscription::none(),
+        };
         Subscription::batch([
             event::listen().map(Message::Event),
             iced::time::every(std::time::Duration::from_millis(TICK_MILIS)).map(|_| Message::Tick),
+            beat,
         ])
     }
 
     fn exit_with_save(&self) -> Task<Message> {
-        Task::perform(self.config.clone().save(), Message::WindowSettingsSaved)
+        let mut config = self.config.clone();
+        config.current_input = self
+            .exercise_components
+            .iter()
+            .map(|ex| ex.input().to_string())
+            .collect();
+        config.current_seed = Some(self.page_seed);
+        Task::perform(config.save(), Message::WindowSettingsSaved)
     }
 
     fn construct_exercise_components(&mut self) {
-        if let Some(lesson) = &self.lesson {
-            if let Some(ex) =
-                lesson.get_exercise(self.config.current_page, self.config.current_exercise)
-            {
-                match ex {
-                    config::Exercise::None => {}
-                    config::Exercise::OneLineNoEnter(line) => {
-                        self.exercise_components.push(ExerciseComponent::new(line));
-                    }
-                    config::Exercise::Multiline(lines) => {
-                        for line in lines.lines() {
-                            let mut ex = ExerciseComponent::new(line);
-                            if self.exercise_components.is_empty() {
-                                ex.update(exercise_component::Message::SetFocus(true))
-                            }
-                            self.exercise_components.push(ex);
+        self.metronome = None;
+        self.paragraph = None;
+        self.shortcuts = None;
+        if let Some(ex) = self.lesson.as_ref().and_then(|lesson| {
+            lesson
+                .get_exercise(self.config.current_page, self.config.current_exercise)
+                .cloned()
+        }) {
+            self.push_exercise(&ex);
+        }
+    }
+
+    fn push_exercise(&mut self, exercise: &config::Exercise) {
+        match exercise {
+            config::Exercise::None => {}
+            config::Exercise::OneLineNoEnter(line) => {
+                let ex = self.new_exercise_component(line);
+                self.exercise_components.push(ex);
+            }
+            config::Exercise::Multiline(lines) => {
+                self.push_lines(lines.lines());
+            }
+            config::Exercise::Shuffle { lines, pick, seed } => {
+                let seed = seed.unwrap_or(self.page_seed);
+                let lines = generator::shuffle_lines(lines, *pick, Some(seed));
+                self.push_lines(lines.iter().map(String::as_str));
+            }
+            config::Exercise::WordPool {
+                words,
+                lines,
+                words_per_line,
+                seed,
+            } => {
+                let words: Vec<&str> = words.split_whitespace().collect();
+                let seed = seed.unwrap_or(self.page_seed);
+                let lines = generator::sample_words(&words, *lines, *words_per_line, Some(seed));
+                self.push_lines(lines.iter().map(String::as_str));
+            }
+            config::Exercise::Paragraph(text) => {
+                self.paragraph = Some(text.clone());
+                let lines = generator::wrap_text(text, self.paragraph_columns());
+                self.push_lines(lines.iter().map(String::as_str));
+            }
+            config::Exercise::Shortcuts(chords) => {
+                let no_key: Vec<&str> = chords
+                    .iter()
+                    .filter(|chord| Chord::parse(chord).is_none())
+                    .map(String::as_str)
+                    .collect();
+                if !no_key.is_empty() {
+                    self.notice = Some(format!(
+                        "Shortcuts without key are skipped: {}",
+                        no_key.join(", ")
+                    ));
+                }
+                self.shortcuts = Some(ShortcutComponent::new(chords));
+                self.apply_settings();
+                self.show_chord();
+            }
+            config::Exercise::Code {
+                language: _,
+                code,
+                file,
+                auto_indent,
+                indent,
+            } => {
+                let code = match file {
+                    Some(file) => match std::fs::read_to_string(Config::data_dir().join(file)) {
+                        Ok(code) => code,
+                        Err(err) => {
+                            self.notice =
+                                Some(format!("Code snippet {} could not be read: {}", file, err));
+                            return;
                         }
+                    },
+                    None => code.clone(),
+                };
+                for line in code.lines() {
+                    let mut ex = self.new_exercise_component(line.trim_end());
+                    ex.update(exercise_component::Message::SetTabWidth(*indent));
+                    ex.update(exercise_component::Message::SetAutoIndent(*auto_indent));
+                    self.exercise_components.push(ex);
+                }
+                self.focus_next_exercise();
+            }
+            config::Exercise::WordList {
+                file,
+                lines,
+                words_per_line,
+                filter,
+                max_length,
+                seed,
+            } => {
+                let text = match std::fs::read_to_string(Config::data_dir().join(file)) {
+                    Ok(text) => text,
+                    Err(err) => {
+                        self.notice =
+                            Some(format!("Word list {} could not be read: {}", file, err));
+                        return;
                     }
+                };
+                let allowed: Option<HashSet<char>> = match filter {
+                    KeyFilter::All => None,
+                    KeyFilter::Introduced => self.lesson.as_ref().map(|lesson| {
+                        self.keyboard
+                            .config()
+                            .typeable_chars(&self.introduced_keys(lesson))
+                    }),
+                    KeyFilter::Keys(keys) => Some(keys.chars().chain([' ']).collect()),
+                };
+                let words = generator::filter_words(
+                    generator::words_from_text(&text),
+                    allowed.as_ref(),
+                    *max_length,
+                );
+                // Misconfigured filter is shown, so page is not passed silently
+                if words.is_empty() {
+                    self.notice = Some(format!(
+                        "Word list {} has no words, which can be typed with allowed keys",
+                        file
+                    ));
+                    return;
                 }
-            };
+                let words: Vec<&str> = words.iter().map(String::as_str).collect();
+                let seed = seed.unwrap_or(self.page_seed);
+                let lines = generator::sample_words(&words, *lines, *words_per_line, Some(seed));
+                self.push_lines(lines.iter().map(String::as_str));
+            }
+            config::Exercise::Metronome { kpm, exercise } => {
+                self.metronome = Some(Metronome::new(*kpm));
+                self.push_exercise(exercise);
+            }
+            config::Exercise::Repeat {
+                times,
+                until_accuracy,
+                in_a_row,
+                exercise,
+            } => {
+                // Attempt results are kept while exercise is respawned
+                if self.repetition.is_none() {
+                    self.repetition = Some(Repetition::new(*times, *until_accuracy, *in_a_row));
+                }
+                self.push_exercise(exercise);
+            }
         }
     }
 
-    fn move_next_page(&mut self) {
+    // Shortcut exercise in progress takes all key presses, so shortcuts
+    // like Ctrl+- or Enter do not trigger application actions.
+    // Only exit lesson binding still opens exit dialog.
+    // Returns true if event was taken.
+    fn shortcut_event(&mut self, event: &Event) -> bool {
+        if self.dialog != DialogType::None {
+            return false;
+        }
+        let Some(shortcuts) = self
+            .shortcuts
+            .as_mut()
+            .filter(|shortcuts| !shortcuts.finished())
+        else {
+            return false;
+        };
+        match event {
+            // Exit binding still works, so student can leave exercise
+            Event::Keyboard(iced::keyboard::Event::KeyPressed { key, modifiers, .. })
+                if self.config.keybindings.action(key, *modifiers) != Some(Action::ExitLesson) => {}
+            Event::Keyboard(iced::keyboard::Event::KeyReleased { .. }) => {}
+            _ => return false,
+        }
+        let sound = shortcuts.update(shortcut_component::Message::Event(event.clone()));
+        let finished = shortcuts.finished();
+        if let Some(sound) = sound {
+            self.play(sound);
+        }
+        if finished {
+            self.play(SoundEvent::ExerciseComplete);
+        }
+        self.keyboard
+            .update(keyboard_component::Message::Event(event.clone()));
+        self.show_chord();
+        true
+    }
+
+    // Highlights keys of current shortcut on keyboard
+    fn show_chord(&mut self) {
+        let keys = self
+            .shortcuts
+            .as_ref()
+            .and_then(|shortcuts| shortcuts.current_chord())
+            .map(|chord| {
+                chord
+                    .keys()
+                    .iter()
+                    .filter_map(|key| self.keyboard.config().find_spec(key))
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.keyboard
+            .update(keyboard_component::Message::SetChordKeys(keys));
+    }
+
+    // Characters fitting in exercise line at current window width
+    fn paragraph_columns(&self) -> usize {
+        let text_size = self.config.text_size(scale::EXERCISE_SIZE);
+        // Page and exercise paddings
+        let width = self.window_width - 60.0 - text_size;
+        // Monospace letter is about 0.6 of font size wide
+        let letter_width = text_size * 0.6 + self.config.accessibility.letter_spacing;
+        (width / letter_width).max(1.0) as usize
+    }
+
+    // Wraps paragraph again after window or text size change.
+    // Once student started typing, wrapping is kept.
+    fn rewrap_paragraph(&mut self) {
+        let Some(text) = self.paragraph.clone() else {
+            return;
+        };
+        if self.exercise_components.iter().any(|ex| ex.started()) {
+            return;
+        }
+        self.exercise_components.clear();
+        let lines = generator::wrap_text(&text, self.paragraph_columns());
+        self.push_lines(lines.iter().map(String::as_str));
+    }
+
+    // One exercise component per line, first line gets focus
+    fn push_lines<'a>(&mut self, lines: impl Iterator<Item = &'a str>) {
+        for line in lines {
+            let mut ex = self.new_exercise_component(line);
+            if self.exercise_components.is_empty() {
+                ex.update(exercise_component::Message::SetFocus(true));
+            }
+            self.exercise_components.push(ex);
+        }
+    }
+
+    fn new_exercise_component(&self, line: &str) -> ExerciseComponent {
+        let mut ex = ExerciseComponent::new(line);
+        for message in self.exercise_settings() {
+            ex.update(message);
+        }
+        ex
+    }
+
+    fn exercise_settings(&self) -> [exercise_component::Message; 3] {
+        let accessibility = &self.config.accessibility;
+        [
+            exercise_component::Message::SetTextSize(self.config.text_size(scale::EXERCISE_SIZE)),
+            exercise_component::Message::SetSteadyCursor(accessibility.steady_cursor),
+            exercise_component::Message::SetLetterSpacing(accessibility.letter_spacing),
+        ]
+    }
+
+    // Pushes current settings to components
+    fn apply_settings(&mut self) {
+        for message in self.exercise_settings() {
+            for exercise_component in self.exercise_components.iter_mut() {
+                exercise_component.update(message.clone());
+            }
+        }
+        let text_size = self.config.text_size(scale::EXERCISE_SIZE);
+        if let Some(shortcuts) = self.shortcuts.as_mut() {
+            shortcuts.update(shortcut_component::Message::SetTextSize(text_size));
+        }
+        let accessibility = &self.config.accessibility;
+        for message in [
+            keyboard_component::Message::SetScale(self.config.ui_scale),
+            keyboard_component::Message::SetStaticHints(accessibility.static_key_hints),
+            keyboard_component::Message::SetHighContrast(accessibility.high_contrast),
+        ] {
+            self.keyboard.update(message);
+        }
+    }
+
+    fn play(&self, event: SoundEvent) {
+        self.beeper.play(event);
+    }
+
+    // Plays events, skipping repeated ones
+    fn play_all(&self, mut events: Vec<SoundEvent>) {
+        events.dedup();
+        for event in events {
+            self.play(event);
+        }
+    }
+
+    // True if page has exercises and all of them are finished
+    fn exercises_finished(&self) -> bool {
+        if let Some(shortcuts) = &self.shortcuts {
+            return shortcuts.finished();
+        }
+        !self.exercise_components.is_empty()
+            && self
+                .exercise_components
+                .iter()
+                .all(|ex| ex.exercise_finished())
+    }
+
+    // Student finished page. Records exercise results and moves to next page,
+    // if page thresholds are met.
+    fn complete_page(&mut self) {
+        if self.exercises_finished() {
+            self.record_stats();
+            if let (Some(repetition), Some(was)) = (self.repetition.as_mut(), &self.was) {
+                repetition.record(was.clone());
+                if !repetition.done() {
+                    // Next attempt with fresh exercise
+                    self.page_seed = generator::new_seed();
+                    self.exercise_components.clear();
+                    self.construct_exercise_components();
+                    return;
+                }
+            }
+            if !self.page_passed() {
+                self.dialog = DialogType::ExerciseFailed;
+                return;
+            }
+            self.passed_pages.insert(self.config.current_page);
+        }
+        self.move_next_page();
+    }
+
+    fn record_stats(&mut self) {
         self.calculate_stats();
+        let page = self.config.current_page;
+        let lesson = self.config.current_lesson.clone();
+        let Some(was) = &self.was else {
+            return;
+        };
+        if self.config.progress.record(&lesson, page, was) {
+            self.play(SoundEvent::NewRecord);
+        }
+    }
+
+    // With mandatory gates student can not move past pages with pass thresholds,
+    // which were not passed yet
+    fn can_skip_to(&self, page: usize) -> bool {
+        let Some(lesson) = &self.lesson else {
+            return true;
+        };
+        self.config.gates != GateMode::Mandatory
+            || lesson
+                .pages
+                .iter()
+                .take(page)
+                .enumerate()
+                .skip(self.config.current_page)
+                .all(|(index, lesson_page)| {
+                    !lesson_page.has_gate() || self.passed_pages.contains(&index)
+                })
+    }
+
+    fn page_passed(&self) -> bool {
+        self.lesson
+            .as_ref()
+            .and_then(|lesson| lesson.get_page(self.config.current_page))
+            .zip(self.was.as_ref())
+            .map(|(page, was)| page.passed(was))
+            .unwrap_or(true)
+    }
+
+    fn repeat_exercise(&mut self) {
+        self.dialog = DialogType::None;
+        self.repetition = None;
+        self.exercise_components.clear();
+        self.construct_exercise_components();
+        self.focus_next_exercise();
+    }
+
+    // Focuses first unfinished exercise line
+    fn focus_next_exercise(&mut self) {
+        for exercise_component in self.exercise_components.iter_mut() {
+            if !exercise_component.exercise_finished() {
+                exercise_component.update(exercise_component::Message::SetFocus(true));
+                break;
+            } else {
+                exercise_component.update(exercise_component::Message::SetFocus(false));
+            }
+        }
+    }
+
+    fn move_next_page(&mut self) {
+        let Some(lesson) = &self.lesson else {
+            return;
+        };
+        let next_page = lesson.next_page(self.config.current_page, self.was.as_ref());
+        self.page_history.push(self.config.current_page);
+        self.config.go_to_page(next_page);
+        if next_page < lesson.pages.len() {
+            self.enter_page();
+            return;
+        }
+        self.play(SoundEvent::LessonComplete);
+        if !self.config.is_custom_lesson() && !self.pages_skipped {
+            let current_lesson = self.config.current_lesson.clone();
+            self.config.progress.complete(&current_lesson);
+        }
+        // Lesson still locked by other prerequisites is chosen from picker,
+        // lessons not suitable for keyboard are passed over
+        let mut next = self.config.index.next_lesson(&self.config.current_lesson);
+        while let Some(record) = next.and_then(|file| self.config.index.find(file)) {
+            if self.keyboard_supports(record) {
+                break;
+            }
+            next = self.config.index.next_lesson(&record.file);
+        }
+        let next = next
+            .filter(|next| {
+                self.config
+                    .missing_prerequisites(next, |record| self.keyboard_supports(record))
+                    .is_empty()
+            })
+            .map(String::from);
+        self.config.go_to_page(0);
+        let Some(name) = next else {
+            self.set_lesson(None);
+            return;
+        };
+        // Broken next lesson is reported in picker
+        match self.config.load_lesson(&name) {
+            Ok(lesson) => self.set_lesson(Some(lesson)),
+            Err(err) => {
+                self.set_lesson(None);
+                self.notice = Some(format!("Lesson {} could not be loaded: {}", name, err));
+            }
+        }
+    }
+
+    // Runs action of pressed key binding
+    fn perform(&mut self, action: Action) -> Task<Message> {
+        let navigation = match action {
+            Action::Next => {
+                match self.dialog {
+                    DialogType::ConfirmExitApp => return self.exit_with_save(),
+                    DialogType::ConfirmExitLesson => {
+                        self.dialog = DialogType::None;
+                        self.set_lesson(None);
+                    }
+                    DialogType::ExerciseFailed => self.repeat_exercise(),
+                    DialogType::None => {
+                        if self.exercises_finished() || self.exercise_components.is_empty() {
+                            self.complete_page();
+                        }
+                    }
+                    _ => {}
+                }
+                None
+            }
+            Action::ExitLesson => {
+                if self.dialog == DialogType::ExerciseFailed {
+                    self.repeat_exercise();
+                } else if self.dialog == DialogType::None {
+                    if self.lesson.is_some() {
+                        self.dialog = DialogType::ConfirmExitLesson;
+                    } else {
+                        self.dialog = DialogType::ConfirmExitApp;
+                    }
+                } else {
+                    self.dialog = DialogType::None;
+                }
+                None
+            }
+            Action::Continue => {
+                if self.dialog == DialogType::ExerciseFailed
+                    && self.config.gates == GateMode::Advisory
+                {
+                    self.dialog = DialogType::None;
+                    self.move_next_page();
+                }
+                None
+            }
+            Action::Pause => {
+                if self.dialog == DialogType::Paused {
+                    self.dialog = DialogType::None;
+                } else if self.dialog == DialogType::None && self.lesson.is_some() {
+                    self.dialog = DialogType::Paused;
+                }
+                None
+            }
+            Action::Stats => {
+                self.toggle_dialog(DialogType::Stats);
+                None
+            }
+            Action::Help => {
+                self.toggle_dialog(DialogType::Help);
+                None
+            }
+            Action::ToggleKeyboard => {
+                self.keyboard_hidden = !self.keyboard_hidden;
+                None
+            }
+            Action::Back => Some(Message::PreviousPage),
+            Action::Skip => Some(Message::NextPage),
+            Action::Restart => Some(Message::RestartPage),
+            Action::RestartLesson => Some(Message::RestartLesson),
+            Action::PageList => Some(Message::OpenPageList),
+        };
+        match navigation {
+            // Page navigation works only within lesson
+            Some(message) if self.lesson.is_some() => self.update(message),
+            _ => Task::none(),
+        }
+    }
 
+    // Opens dialog or closes it, if it is already shown
+    fn toggle_dialog(&mut self, dialog: DialogType) {
+        self.dialog = if self.dialog == dialog {
+            DialogType::None
+        } else {
+            dialog
+        };
+    }
+
+    fn go_to_page(&mut self, page: usize) {
+        self.config.go_to_page(page);
+        self.enter_page();
+    }
+
+    // Prepares current page of lesson: keyboard hints and hands, exercise
+    // components with input restored from config and focus. Used at start,
+    // on lesson selection, resume and page change.
+    fn enter_page(&mut self) {
+        self.repetition = None;
         self.exercise_components.clear();
         self.keyboard.update(keyboard_component::Message::ClearKeys);
-        self.config.next_page();
+        let Some(page) = self
+            .lesson
+            .as_ref()
+            .and_then(|lesson| lesson.get_page(self.config.current_page))
+        else {
+            return;
+        };
+        let (show_keys, hands) = (page.show_keys.clone(), page.hands);
+        if !show_keys.is_empty() {
+            self.keyboard
+                .update(keyboard_component::Message::SetShowKeys(show_keys));
+        }
+        self.keyboard
+            .update(keyboard_component::Message::SetHands(hands));
+        // Input and seed are kept only till first page is entered after start
+        self.page_seed = self
+            .config
+            .current_seed
+            .take()
+            .unwrap_or_else(generator::new_seed);
+        self.construct_exercise_components();
+        let input = std::mem::take(&mut self.config.current_input);
+        for (exercise_component, typed) in self.exercise_components.iter_mut().zip(input) {
+            exercise_component.update(exercise_component::Message::SetInput(typed));
+        }
+        self.focus_next_exercise();
+    }
+
+    fn calculate_stats(&mut self) {
+        let rhythm = self
+            .metronome
+            .as_ref()
+            .and_then(|metronome| metronome.consistency());
+        self.was = Some(match &self.shortcuts {
+            Some(shortcuts) => Stats::from_shortcuts(shortcuts),
+            None => Stats::new(&self.exercise_components, rhythm),
+        });
+        self.was_page = self.config.current_page;
+    }
+
+    // Starts lesson at current page or shows lesson picker, if lesson is None
+    fn set_lesson(&mut self, lesson: Option<Lesson>) {
+        self.page_history.clear();
+        self.pages_skipped = false;
+        self.passed_pages.clear();
+        self.was = None;
+        self.renderer.set_lesson(lesson.as_ref());
+        self.lesson = lesson;
+        self.notice = None;
         if let Some(lesson) = &self.lesson {
-            if let Some(page) = lesson.get_page(self.config.current_page) {
-                if !page.show_keys.is_empty() {
-                    self.keyboard
-                        .update(keyboard_component::Message::SetShowKeys(
-                            page.show_keys.clone(),
-                        ))
+            // Saved page may be gone, if lesson file was edited
+            if self.config.current_page >= lesson.pages.len() {
+                self.config.go_to_page(0);
+                self.config.current_input.clear();
+                self.config.current_seed = None;
+            }
+            self.earlier_keys = match self.config.keys_before_lesson() {
+                Ok(keys) => keys,
+                Err(err) => {
+                    self.notice = Some(format!("Keys of earlier lessons are not known: {}", err));
+                    vec![]
                 }
-                self.construct_exercise_components();
-            } else {
-                self.lesson = self
-                    .config
-                    .index
-                    .next_lesson(&self.config.current_lesson)
-                    .map(String::from)
-                    .map(|name| {
-                        self.config
-                            .load_lesson(&name)
-                            .expect("Enable to load next lesson")
-                    });
-                self.config.current_exercise = 0;
-                self.config.current_page = 0;
+            };
+            self.enter_page();
+        } else {
+            self.repetition = None;
+            self.exercise_components.clear();
+            self.keyboard.update(keyboard_component::Message::ClearKeys);
+            self.metronome = None;
+            self.paragraph = None;
+            self.shortcuts = None;
+            self.config.current_input.clear();
+            self.config.current_seed = None;
+        }
+    }
+
+    // Keys of earlier lessons and of lesson pages up to current one
+    fn introduced_keys(&self, lesson: &Lesson) -> Vec<PressedKeyCoord> {
+        let mut keys = self.earlier_keys.clone();
+        keys.extend(
+            lesson
+                .pages
+                .iter()
+                .take(self.config.current_page + 1)
+                .flat_map(|page| page.show_keys.iter().cloned()),
+        );
+        keys
+    }
+
+    // Lesson can be typed with current keyboard
+    fn keyboard_supports(&self, record: &IndexRecord) -> bool {
+        !record.numpad || self.keyboard.config().has_numpad()
+    }
+
+    fn refresh_custom_texts(&mut self) {
+        match custom_text::files() {
+            Ok(files) => self.custom_texts = files,
+            Err(err) => {
+                self.custom_texts.clear();
+                self.notice = Some(err.to_string());
             }
         }
     }
 
-    fn calculate_stats(&mut self) {
-        let mut errors: u64 = 0;
-        let mut mseconds: u64 = 0;
-        let mut length: u64 = 0;
-        for ex in &self.exercise_components {
-            errors += ex.errors;
-            mseconds += ex.mseconds;
-            length += ex.exercise.chars().map(|_| 1).sum::<u64>();
-        }
-        self.was_errors = errors.round();
-        let was_wpm = ((length as f64 - errors as f64) / (mseconds as f64 / 60000.0)) / 5.0;
-        self.was_wpm = (was_wpm * 100.0).round() / 100.0;
+    // Runs user text as one page lesson with stats recorded as custom session
+    fn start_custom_lesson(&mut self, name: &str, content: &str) {
+        let allowed = self
+            .keyboard
+            .config()
+            .typeable_chars(&self.keyboard.config().all_keys());
+        let content = custom_text::normalize(content, &allowed);
+        if content.is_empty() {
+            self.notice = Some(format!(
+                "Custom text {} has nothing to type with current keyboard",
+                name
+            ));
+            return;
+        }
+        let lesson = self.config.load_custom_lesson(name, &content);
+        self.set_lesson(Some(lesson));
+    }
+
+    fn page_context(&self) -> PageContext {
+        let current_lesson = &self.config.current_lesson;
+        let page = self
+            .lesson
+            .as_ref()
+            .and_then(|lesson| lesson.get_page(self.config.current_page));
+        PageContext {
+            stats: self.was.clone().unwrap_or_default(),
+            net_wpm: self.was.as_ref().map_or(0.0, |was| was.wpm),
+            best_wpm: self
+                .config
+                .progress
+                .page(current_lesson, self.was_page)
+                .map(|result| result.best_wpm),
+            page: self.config.current_page + 1,
+            pages: self
+                .lesson
+                .as_ref()
+                .map(|lesson| lesson.pages.len())
+                .unwrap_or_default(),
+            lesson: self
+                .config
+                .index
+                .lessons
+                .iter()
+                .find(|record| record.file.eq(current_lesson))
+                .map(|record| record.title.clone())
+                .or_else(|| {
+                    current_lesson
+                        .strip_prefix(custom_text::LESSON_PREFIX)
+                        .map(String::from)
+                })
+                .unwrap_or_default(),
+            student: self.config.student_name.clone(),
+            keyboard: self.keyboard.name().to_string(),
+            min_wpm: page.and_then(|page| page.min_wpm),
+            max_error_rate: page.and_then(|page| page.max_error_rate),
+            keys: self.config.keybindings.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lesson() -> Lesson {
+        serde_yaml::from_str(
+            "pages:
+               - title: Home row
+                 show_keys:
+                   - row: 2
+                     key: 1
+                 hands: true
+                 exercises:
+                   - !OneLineNoEnter asdf
+               - title: Practice
+                 exercises:
+                   - !Multiline |-
+                       asdf
+                       jkl;",
+        )
+        .unwrap()
+    }
+
+    fn inputs(raiti: &Raiti) -> Vec<&str> {
+        raiti
+            .exercise_components
+            .iter()
+            .map(|ex| ex.input())
+            .collect()
+    }
+
+    fn focused(raiti: &Raiti) -> Vec<bool> {
+        raiti
+            .exercise_components
+            .iter()
+            .map(|ex| ex.focused())
+            .collect()
+    }
+
+    #[test]
+    fn lesson_selection_enters_first_page() {
+        let mut raiti = Raiti::default();
+        raiti.set_lesson(Some(lesson()));
+        assert_eq!(
+            raiti.keyboard.show_keys(),
+            &[PressedKeyCoord { row: 2, key: 1 }]
+        );
+        assert!(raiti.keyboard.hands());
+        assert_eq!(inputs(&raiti), vec![""]);
+        assert_eq!(focused(&raiti), vec![true]);
+    }
+
+    #[test]
+    fn page_advance_resets_keys_and_hands() {
+        let mut raiti = Raiti::default();
+        raiti.set_lesson(Some(lesson()));
+        raiti.move_next_page();
+        assert_eq!(raiti.config.current_page, 1);
+        assert!(raiti.keyboard.show_keys().is_empty());
+        assert!(!raiti.keyboard.hands());
+        assert_eq!(inputs(&raiti), vec!["", ""]);
+        assert_eq!(focused(&raiti), vec![true, false]);
+    }
+
+    #[test]
+    fn startup_restores_saved_input() {
+        let mut raiti = Raiti::default();
+        raiti.config.current_page = 1;
+        raiti.config.current_input = vec!["asdf".to_string(), "jk".to_string()];
+        raiti.set_lesson(Some(lesson()));
+        assert_eq!(inputs(&raiti), vec!["asdf", "jk"]);
+        assert_eq!(focused(&raiti), vec![false, true]);
+        // Restored input is not counted in stats
+        assert_eq!(raiti.exercise_components[1].prefilled, 2);
+        assert!(raiti.config.current_input.is_empty());
+    }
+
+    #[test]
+    fn input_not_matching_exercise_is_dropped() {
+        let mut raiti = Raiti::default();
+        raiti.config.current_input = vec!["xyz".to_string()];
+        raiti.set_lesson(Some(lesson()));
+        assert_eq!(inputs(&raiti), vec![""]);
+        assert_eq!(raiti.exercise_components[0].prefilled, 0);
+    }
+
+    #[test]
+    fn input_is_restored_only_on_first_page_entered() {
+        let mut raiti = Raiti::default();
+        raiti.config.current_input = vec!["as".to_string()];
+        raiti.set_lesson(Some(lesson()));
+        raiti.go_to_page(0);
+        assert_eq!(inputs(&raiti), vec![""]);
+    }
+
+    #[test]
+    fn startup_restores_input_of_random_lines() {
+        let lines = generator::shuffle_lines("asdf\njkl;\nfdsa", None, Some(7));
+        let lesson: Lesson = serde_yaml::from_str(
+            "pages:
+               - title: Shuffled
+                 exercises:
+                   - !Shuffle
+                     lines: |
+                       asdf
+                       jkl;
+                       fdsa",
+        )
+        .unwrap();
+        let mut raiti = Raiti::default();
+        raiti.config.current_seed = Some(7);
+        raiti.config.current_input = vec![lines[0].clone()];
+        raiti.set_lesson(Some(lesson));
+        assert_eq!(inputs(&raiti), vec![lines[0].as_str(), "", ""]);
+        assert_eq!(raiti.config.current_seed, None);
+        assert_eq!(raiti.page_seed, 7);
+    }
+
+    #[test]
+    fn saved_page_outside_lesson_starts_first_page() {
+        let mut raiti = Raiti::default();
+        raiti.config.current_page = 5;
+        raiti.config.current_input = vec!["asdf".to_string()];
+        raiti.set_lesson(Some(lesson()));
+        assert_eq!(raiti.config.current_page, 0);
+        assert_eq!(inputs(&raiti), vec![""]);
+        assert_eq!(focused(&raiti), vec![true]);
+    }
+
+    #[test]
+    fn resume_message_keeps_saved_page_and_exercise() {
+        let mut raiti = Raiti::default();
+        raiti.config.current_lesson = "l02_base_keys".to_string();
+        raiti.config.current_page = 6;
+        raiti.config.current_exercise = 0;
+        raiti.config.current_input = vec!["asdf".to_string()];
+        let _ = raiti.update(Message::ResumeLesson);
+        assert!(raiti.lesson.is_some());
+        assert!(raiti.notice.is_none());
+        assert_eq!(raiti.config.current_page, 6);
+        assert_eq!(raiti.config.current_exercise, 0);
+        assert_eq!(inputs(&raiti), vec!["asdf"]);
+    }
+
+    #[test]
+    fn resume_enters_saved_page() {
+        let mut raiti = Raiti::default();
+        raiti.config.current_page = 1;
+        raiti.set_lesson(Some(lesson()));
+        raiti.set_lesson(None);
+        assert!(raiti.exercise_components.is_empty());
+        raiti.config.current_page = 1;
+        raiti.set_lesson(Some(lesson()));
+        assert_eq!(raiti.config.current_page, 1);
+        assert!(!raiti.keyboard.hands());
+        assert_eq!(focused(&raiti), vec![true, false]);
+    }
+
+    fn gated_lesson() -> Lesson {
+        serde_yaml::from_str(
+            "pages:
+               - title: Intro
+               - title: Speed
+                 min_wpm: 10
+                 exercises:
+                   - !OneLineNoEnter asdf
+               - title: End",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn back_returns_to_page_left_and_forgets_results() {
+        let mut raiti = Raiti::default();
+        raiti.set_lesson(Some(gated_lesson()));
+        let _ = raiti.update(Message::JumpToPage(2));
+        assert_eq!(raiti.config.current_page, 2);
+        raiti.was = Some(Stats::default());
+        let _ = raiti.update(Message::PreviousPage);
+        assert_eq!(raiti.config.current_page, 0);
+        assert!(raiti.was.is_none());
+        // Without history previous page is page before current one
+        let _ = raiti.update(Message::PreviousPage);
+        assert_eq!(raiti.config.current_page, 0);
+    }
+
+    #[test]
+    fn skip_moves_to_next_page_and_marks_lesson_skipped() {
+        let mut raiti = Raiti::default();
+        raiti.set_lesson(Some(gated_lesson()));
+        raiti.was = Some(Stats::default());
+        let _ = raiti.update(Message::NextPage);
+        assert_eq!(raiti.config.current_page, 1);
+        assert!(raiti.pages_skipped);
+        assert!(raiti.was.is_none());
+        let _ = raiti.update(Message::RestartLesson);
+        assert_eq!(raiti.config.current_page, 0);
+        assert!(!raiti.pages_skipped);
+    }
+
+    #[test]
+    fn mandatory_gate_blocks_skipping_unpassed_page() {
+        let mut raiti = Raiti::default();
+        raiti.config.gates = GateMode::Mandatory;
+        raiti.set_lesson(Some(gated_lesson()));
+        let _ = raiti.update(Message::NextPage);
+        assert_eq!(raiti.config.current_page, 1);
+        let _ = raiti.update(Message::NextPage);
+        assert_eq!(raiti.config.current_page, 1);
+        let _ = raiti.update(Message::JumpToPage(2));
+        assert_eq!(raiti.config.current_page, 1);
+        raiti.passed_pages.insert(1);
+        let _ = raiti.update(Message::JumpToPage(2));
+        assert_eq!(raiti.config.current_page, 2);
+    }
+
+    #[test]
+    fn advisory_gate_allows_skipping() {
+        let mut raiti = Raiti::default();
+        raiti.config.gates = GateMode::Advisory;
+        raiti.set_lesson(Some(gated_lesson()));
+        let _ = raiti.update(Message::JumpToPage(2));
+        assert_eq!(raiti.config.current_page, 2);
+    }
+
+    #[test]
+    fn lesson_with_skipped_pages_is_not_completed() {
+        let mut raiti = Raiti::default();
+        raiti.config.current_lesson = "l01".to_string();
+        raiti.set_lesson(Some(lesson()));
+        let _ = raiti.update(Message::NextPage);
+        let _ = raiti.update(Message::NextPage);
+        assert!(raiti.lesson.is_none());
+        assert!(!raiti.config.progress.completed("l01"));
+
+        raiti.config.current_lesson = "l01".to_string();
+        raiti.set_lesson(Some(lesson()));
+        raiti.move_next_page();
+        raiti.move_next_page();
+        assert!(raiti.lesson.is_none());
+        assert!(raiti.config.progress.completed("l01"));
     }
 }
@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+// Keeps beat for rhythm exercises and measures how far
+// key presses are from the beat.
+#[derive(Debug, Clone)]
+pub struct Metronome {
+    pub interval: Duration,
+    last_beat: Option<Instant>,
+    // Key press distance to nearest beat as part of interval (0.0 - 0.5)
+    deviations: Vec<f64>,
+}
+
+impl Metronome {
+    // Metronome for given rate in keys per minute
+    pub fn new(keys_per_minute: u32) -> Self {
+        // Beat interval is at least one millisecond, timer panics on zero interval
+        let keys_per_minute = keys_per_minute.clamp(1, 60_000) as u64;
+        Metronome {
+            interval: Duration::from_millis(60_000 / keys_per_minute),
+            last_beat: None,
+            deviations: vec![],
+        }
+    }
+
+    pub fn beat(&mut self) {
+        self.last_beat = Some(Instant::now());
+    }
+
+    pub fn key_pressed(&mut self) {
+        // Key presses before first beat can not be measured
+        let Some(last_beat) = self.last_beat else {
+            return;
+        };
+        let since_beat = last_beat.elapsed().as_secs_f64();
+        let phase = (since_beat / self.interval.as_secs_f64()).fract();
+        self.deviations.push(phase.min(1.0 - phase));
+    }
+
+    // Rhythm consistency in percents. 100 means every key was pressed on beat.
+    pub fn consistency(&self) -> Option<f64> {
+        if self.deviations.is_empty() {
+            return None;
+        }
+        let mean = self.deviations.iter().sum::<f64>() / self.deviations.len() as f64;
+        let consistency = (1.0 - mean * 2.0) * 100.0;
+        Some((consistency * 100.0).round() / 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_follows_keys_per_minute() {
+        assert_eq!(Metronome::new(120).interval, Duration::from_millis(500));
+        assert_eq!(Metronome::new(0).interval, Duration::from_secs(60));
+        assert_eq!(Metronome::new(100_000).interval, Duration::from_millis(1));
+    }
+
+    #[test]
+    fn key_presses_before_first_beat_are_not_measured() {
+        let mut metronome = Metronome::new(120);
+        metronome.key_pressed();
+        assert_eq!(metronome.consistency(), None);
+    }
+
+    #[test]
+    fn key_pressed_on_beat_is_consistent() {
+        // Long interval, so key press is close to beat
+        let mut metronome = Metronome::new(1);
+        metronome.beat();
+        metronome.key_pressed();
+        assert!(metronome.consistency().unwrap() > 99.0);
+    }
+
+    #[test]
+    fn consistency_is_mean_distance_from_beat() {
+        let mut metronome = Metronome::new(120);
+        metronome.deviations = vec![0.0, 0.25];
+        assert_eq!(metronome.consistency(), Some(75.0));
+        metronome.deviations = vec![0.5];
+        assert_eq!(metronome.consistency(), Some(0.0));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::stats::Stats;
+
+// Tracks attempts of repeated exercise
+#[derive(Debug, Clone, Default)]
+pub struct Repetition {
+    times: Option<u32>,
+    until_accuracy: Option<f64>,
+    in_a_row: u32,
+    pub results: Vec<Stats>,
+}
+
+impl Repetition {
+    pub fn new(times: Option<u32>, until_accuracy: Option<f64>, in_a_row: u32) -> Self {
+        Repetition {
+            times,
+            until_accuracy,
+            in_a_row: in_a_row.max(1),
+            results: vec![],
+        }
+    }
+
+    pub fn record(&mut self, stats: Stats) {
+        self.results.push(stats);
+    }
+
+    // Attempts in a row, which reached target accuracy
+    fn streak(&self, accuracy: f64) -> u32 {
+        self.results
+            .iter()
+            .rev()
+            .take_while(|stats| stats.accuracy >= accuracy)
+            .count() as u32
+    }
+
+    // Exercise is done when target accuracy is reached required times in a row.
+    // With times exercise is repeated given number of times (or at most given
+    // number of times, if target accuracy is set as well).
+    pub fn done(&self) -> bool {
+        let attempts = self.results.len() as u32;
+        if self.times.is_some_and(|times| attempts >= times) {
+            return true;
+        }
+        match self.until_accuracy {
+            Some(accuracy) => self.streak(accuracy) >= self.in_a_row,
+            None => self.times.is_none() && attempts > 0,
+        }
+    }
+
+    pub fn status(&self) -> String {
+        let attempt = self.results.len() + 1;
+        let mut status = match self.times {
+            Some(times) => format!("Attempt {} of {}", attempt, times),
+            None => format!("Attempt {}", attempt),
+        };
+        if let Some(accuracy) = self.until_accuracy {
+            status.push_str(&format!(
+                ". Reach {}% accuracy {} time(s) in a row ({} so far)",
+                accuracy,
+                self.in_a_row,
+                self.streak(accuracy)
+            ));
+        }
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(accuracy: f64) -> Stats {
+        Stats {
+            accuracy,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fixed_times_is_done_after_all_attempts() {
+        let mut repetition = Repetition::new(Some(2), None, 1);
+        repetition.record(attempt(100.0));
+        assert!(!repetition.done());
+        repetition.record(attempt(50.0));
+        assert!(repetition.done());
+    }
+
+    #[test]
+    fn target_accuracy_must_be_reached_in_a_row() {
+        let mut repetition = Repetition::new(None, Some(95.0), 2);
+        repetition.record(attempt(96.0));
+        repetition.record(attempt(90.0));
+        repetition.record(attempt(97.0));
+        assert!(!repetition.done());
+        assert_eq!(
+            repetition.status(),
+            "Attempt 4. Reach 95% accuracy 2 time(s) in a row (1 so far)"
+        );
+        repetition.record(attempt(95.0));
+        assert!(repetition.done());
+    }
+
+    #[test]
+    fn times_limit_attempts_for_target_accuracy() {
+        let mut repetition = Repetition::new(Some(2), Some(95.0), 1);
+        repetition.record(attempt(80.0));
+        repetition.record(attempt(85.0));
+        assert!(repetition.done());
+    }
+
+    #[test]
+    fn zero_in_a_row_requires_one_attempt() {
+        let mut repetition = Repetition::new(None, Some(95.0), 0);
+        assert!(!repetition.done());
+        repetition.record(attempt(100.0));
+        assert!(repetition.done());
+    }
+}
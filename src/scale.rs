@@ -0,0 +1,35 @@
+// Global UI scale. All text sizes are multiplied by the scale stored in config,
+// so exercise text, page text and keyboard labels grow together.
+
+pub const DEFAULT: f32 = 1.0;
+pub const MIN: f32 = 0.5;
+pub const MAX: f32 = 3.0;
+pub const STEP: f32 = 0.1;
+
+pub const TITLE_SIZE: f32 = 25.0;
+pub const TEXT_SIZE: f32 = 16.0;
+// Headings of lesson page blocks
+pub const HEADING_SIZE: f32 = 20.0;
+pub const EXERCISE_SIZE: f32 = 20.0;
+// Key hints at bottom of page
+pub const HINT_SIZE: f32 = 12.0;
+// Height of lesson page pictures
+pub const IMAGE_HEIGHT: f32 = 200.0;
+
+// Keyboard label height relative to key size at scale 1.0
+pub const KEY_LABEL_RATIO: f32 = 0.28;
+
+pub fn clamp(scale: f32) -> f32 {
+    if !scale.is_finite() || scale <= 0.0 {
+        return DEFAULT;
+    }
+    (scale.clamp(MIN, MAX) * 10.0).round() / 10.0
+}
+
+pub fn increase(scale: f32) -> f32 {
+    clamp(scale + STEP)
+}
+
+pub fn decrease(scale: f32) -> f32 {
+    clamp(scale - STEP)
+}
@@ -0,0 +1,267 @@
+use iced::{
+    widget::{button, checkbox, column, pick_list, row, slider, text, text_input},
+    Element,
+};
+
+use crate::{
+    config::{Action, Config},
+    font, scale,
+    sound::{SoundEvent, SoundTheme},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    StudentNameChanged(String),
+    IncreaseScale,
+    DecreaseScale,
+    ExerciseFontSelected(String),
+    UiFontSelected(String),
+    SteadyCursorToggled(bool),
+    StaticKeyHintsToggled(bool),
+    DyslexiaFontToggled(bool),
+    LetterSpacingChanged(f32),
+    HighContrastToggled(bool),
+    SoundPackSelected(String),
+    VolumeChanged(f32),
+    MuteToggled(bool),
+    SoundEventToggled(SoundEvent, bool),
+}
+
+// Label used in font lists for iced default UI font
+const DEFAULT_UI_FONT: &str = "Default";
+
+#[derive(Default)]
+pub struct SettingsComponent {
+    fonts: Vec<String>,
+    sound_packs: Vec<String>,
+}
+
+impl SettingsComponent {
+    pub fn new() -> SettingsComponent {
+        SettingsComponent {
+            fonts: vec![],
+            sound_packs: SoundTheme::packs(),
+        }
+    }
+
+    // Listing system fonts is slow, so it is done when settings are opened
+    pub fn load_fonts(&mut self) {
+        if self.fonts.is_empty() {
+            self.fonts = font::families();
+        }
+    }
+
+    // Applies settings change to config
+    pub fn update(&mut self, message: Message, config: &mut Config) {
+        match message {
+            Message::StudentNameChanged(name) => {
+                config.student_name = name;
+            }
+            Message::IncreaseScale => {
+                config.ui_scale = scale::increase(config.ui_scale);
+            }
+            Message::DecreaseScale => {
+                config.ui_scale = scale::decrease(config.ui_scale);
+            }
+            Message::ExerciseFontSelected(family) => {
+                config.exercise_font = family;
+            }
+            Message::UiFontSelected(family) => {
+                config.ui_font = if family == DEFAULT_UI_FONT {
+                    String::new()
+                } else {
+                    family
+                };
+            }
+            Message::SteadyCursorToggled(value) => {
+                config.accessibility.steady_cursor = value;
+            }
+            Message::StaticKeyHintsToggled(value) => {
+                config.accessibility.static_key_hints = value;
+            }
+            Message::DyslexiaFontToggled(value) => {
+                config.accessibility.dyslexia_font = value;
+            }
+            Message::LetterSpacingChanged(value) => {
+                config.accessibility.letter_spacing = value;
+            }
+            Message::HighContrastToggled(value) => {
+                config.accessibility.high_contrast = value;
+            }
+            Message::SoundPackSelected(pack) => {
+                config.sound.pack = pack;
+            }
+            Message::VolumeChanged(volume) => {
+                config.sound.volume = volume;
+            }
+            Message::MuteToggled(mute) => {
+                config.sound.mute = mute;
+            }
+            Message::SoundEventToggled(event, enabled) => {
+                config.sound.events.retain(|e| *e != event);
+                if enabled {
+                    config.sound.events.push(event);
+                }
+            }
+        }
+    }
+
+    pub fn view(&self, config: &Config) -> Element<'_, Message> {
+        let text_size = config.text_size(scale::TEXT_SIZE);
+
+        let scale_row = row![
+            text(format!("Text size: {:.0}%", config.ui_scale * 100.0)).size(text_size),
+            button(text("-").size(text_size)).on_press(Message::DecreaseScale),
+            button(text("+").size(text_size)).on_press(Message::IncreaseScale),
+        ]
+        .spacing(10);
+
+        // Font is not embedded, so option is available only when it is installed.
+        // Enabled option can still be turned off.
+        let dyslexia_available = self
+            .fonts
+            .iter()
+            .any(|family| family == font::DYSLEXIA_FAMILY);
+        let dyslexia_label = if dyslexia_available {
+            format!("Dyslexia friendly font ({})", font::DYSLEXIA_FAMILY)
+        } else {
+            format!(
+                "Dyslexia friendly font ({} is not installed)",
+                font::DYSLEXIA_FAMILY
+            )
+        };
+        let dyslexia_font = checkbox(dyslexia_label, config.accessibility.dyslexia_font)
+            .on_toggle_maybe(
+                (dyslexia_available || config.accessibility.dyslexia_font)
+                    .then_some(Message::DyslexiaFontToggled),
+            )
+            .text_size(text_size);
+
+        let exercise_font = row![
+            text("Exercise font:").size(text_size),
+            pick_list(
+                self.fonts.clone(),
+                Some(config.exercise_font.clone()),
+                Message::ExerciseFontSelected,
+            )
+            .text_size(text_size),
+        ]
+        .spacing(10);
+
+        let mut ui_fonts = vec![DEFAULT_UI_FONT.to_string()];
+        ui_fonts.extend(self.fonts.iter().cloned());
+        let ui_font_selected = if config.ui_font.is_empty() {
+            DEFAULT_UI_FONT.to_string()
+        } else {
+            config.ui_font.clone()
+        };
+        let ui_font = row![
+            text("Interface font:").size(text_size),
+            pick_list(ui_fonts, Some(ui_font_selected), Message::UiFontSelected)
+                .text_size(text_size),
+        ]
+        .spacing(10);
+
+        let accessibility = &config.accessibility;
+        let letter_spacing = row![
+            text(format!(
+                "Letter spacing: {:.0}",
+                accessibility.letter_spacing
+            ))
+            .size(text_size),
+            slider(
+                0.0..=10.0,
+                accessibility.letter_spacing,
+                Message::LetterSpacingChanged
+            )
+            .step(1.0)
+            .width(200),
+        ]
+        .spacing(10);
+
+        let sound = &config.sound;
+        let sound_pack = row![
+            text("Sound pack:").size(text_size),
+            pick_list(
+                self.sound_packs.clone(),
+                Some(sound.pack.clone()),
+                Message::SoundPackSelected
+            )
+            .text_size(text_size),
+        ]
+        .spacing(10);
+        let volume = row![
+            text(format!("Volume: {:.0}%", sound.volume * 100.0)).size(text_size),
+            slider(0.0..=1.0, sound.volume, Message::VolumeChanged)
+                .step(0.05)
+                .width(200),
+            checkbox("Mute", sound.mute)
+                .on_toggle(Message::MuteToggled)
+                .text_size(text_size),
+        ]
+        .spacing(10);
+        let mut sound_events = column![].spacing(5);
+        for event in SoundEvent::ALL {
+            sound_events = sound_events.push(
+                checkbox(event.label(), sound.events.contains(&event))
+                    .on_toggle(move |enabled| Message::SoundEventToggled(event, enabled))
+                    .text_size(text_size),
+            );
+        }
+
+        column![
+            text("Settings").size(config.text_size(scale::TITLE_SIZE)),
+            row![
+                text("Your name:").size(text_size),
+                text_input("Student", &config.student_name)
+                    .on_input(Message::StudentNameChanged)
+                    .size(text_size)
+                    .width(300),
+            ]
+            .spacing(10),
+            scale_row,
+            exercise_font,
+            ui_font,
+            text("Accessibility").size(config.text_size(scale::TITLE_SIZE)),
+            checkbox("Non-blinking cursor", accessibility.steady_cursor)
+                .on_toggle(Message::SteadyCursorToggled)
+                .text_size(text_size),
+            checkbox(
+                "Highlight keys to learn instead of flashing them",
+                accessibility.static_key_hints
+            )
+            .on_toggle(Message::StaticKeyHintsToggled)
+            .text_size(text_size),
+            checkbox("High contrast keyboard", accessibility.high_contrast)
+                .on_toggle(Message::HighContrastToggled)
+                .text_size(text_size),
+            dyslexia_font,
+            letter_spacing,
+            text("Sound").size(config.text_size(scale::TITLE_SIZE)),
+            sound_pack,
+            volume,
+            sound_events,
+            text(format!(
+                "Own sound packs are folders in {}. Pack can contain WAV/OGG/MP3 files \
+                 named keypress, error, exercise_complete, lesson_complete and new_record.",
+                SoundTheme::user_packs_dir().display()
+            ))
+            .size(text_size),
+            text(format!(
+                "Font changes take effect after restart. Put your own TTF/OTF files into {} \
+                 folder - they are also used as fallback for characters missing in selected font. \
+                 No fallback font is embedded, so for Cyrillic, Greek and other scripts \
+                 missing in selected font put a font having them there.",
+                font::user_fonts_dir().display()
+            ))
+            .size(text_size),
+            text(format!(
+                "Press <{}> to close settings",
+                config.keybindings.chord(Action::ExitLesson)
+            ))
+            .size(text_size),
+        ]
+        .spacing(15)
+        .into()
+    }
+}
@@ -0,0 +1,414 @@
+use std::time::{Duration, Instant};
+
+use iced::{
+    keyboard::{key, Modifiers},
+    widget::{column, text},
+    Element, Event,
+};
+
+use crate::{font, keyboard_config::Key, scale, sound::SoundEvent};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    Event(Event),
+    SetTextSize(f32),
+    // Chord time does not run while paused
+    SetPaused(bool),
+}
+
+// Names of keys as iced reports them, used to write chords in any case
+const NAMED_KEYS: [&str; 17] = [
+    "Enter",
+    "Tab",
+    "Space",
+    "Backspace",
+    "Escape",
+    "Delete",
+    "Insert",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+    "ArrowUp",
+    "ArrowDown",
+    "ArrowLeft",
+    "ArrowRight",
+    "CapsLock",
+    "ContextMenu",
+];
+
+// Key combination, e.g. Ctrl+Shift+T
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chord {
+    pub label: String,
+    modifiers: Modifiers,
+    key: Key,
+}
+
+impl Chord {
+    // Parses chord written as modifiers and key joined with +.
+    // Mod is Cmd on macOS and Ctrl on other systems.
+    pub fn parse(chord: &str) -> Option<Chord> {
+        let mut modifiers = Modifiers::empty();
+        let mut chord_key = None;
+        for part in chord.split('+').map(str::trim) {
+            // "Ctrl++" means Ctrl and + key
+            let part = if part.is_empty() { "+" } else { part };
+            match part.to_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= Modifiers::CTRL,
+                "shift" => modifiers |= Modifiers::SHIFT,
+                "alt" | "option" | "opt" => modifiers |= Modifiers::ALT,
+                "cmd" | "command" | "super" | "win" | "meta" => modifiers |= Modifiers::LOGO,
+                "mod" => {
+                    modifiers |= if cfg!(target_os = "macos") {
+                        Modifiers::LOGO
+                    } else {
+                        Modifiers::CTRL
+                    }
+                }
+                _ => chord_key = Some(Self::key(part)),
+            }
+        }
+        Some(Chord {
+            label: chord.to_string(),
+            modifiers,
+            key: chord_key?,
+        })
+    }
+
+    // Key names are case insensitive, e.g. enter or f1
+    fn key(name: &str) -> Key {
+        if name.chars().count() == 1 {
+            return Key::Character(name.to_lowercase());
+        }
+        let lowercase = name.to_lowercase();
+        let named = match lowercase.as_str() {
+            "esc" => "Escape".to_string(),
+            "del" => "Delete".to_string(),
+            "ins" => "Insert".to_string(),
+            "up" => "ArrowUp".to_string(),
+            "down" => "ArrowDown".to_string(),
+            "left" => "ArrowLeft".to_string(),
+            "right" => "ArrowRight".to_string(),
+            "pgup" => "PageUp".to_string(),
+            "pgdn" => "PageDown".to_string(),
+            function if function.starts_with('f') && function[1..].parse::<u8>().is_ok() => {
+                function.to_uppercase()
+            }
+            _ => NAMED_KEYS
+                .iter()
+                .find(|named| named.eq_ignore_ascii_case(name))
+                .map_or(name.to_string(), |named| named.to_string()),
+        };
+        Key::Named(named)
+    }
+
+    pub fn matches(&self, key: &iced::keyboard::Key, modifiers: Modifiers) -> bool {
+        // Symbols like + or ? are typed with Shift on some layouts, so Shift
+        // is ignored for characters without case, unless chord has it
+        let modifiers = match key {
+            iced::keyboard::Key::Character(character)
+                if !self.modifiers.shift()
+                    && character.to_lowercase() == character.to_uppercase() =>
+            {
+                modifiers.difference(Modifiers::SHIFT)
+            }
+            _ => modifiers,
+        };
+        if modifiers != self.modifiers {
+            return false;
+        }
+        match (key, &self.key) {
+            (iced::keyboard::Key::Named(name), Key::Named(my_name)) => {
+                format!("{:?}", name).eq_ignore_ascii_case(my_name)
+            }
+            (iced::keyboard::Key::Character(character), Key::Character(my_character)) => {
+                character.to_lowercase().eq(my_character)
+            }
+            _ => false,
+        }
+    }
+
+    // Chords are same, if they have same modifiers and key
+    pub fn same_keys(&self, other: &Chord) -> bool {
+        self.modifiers == other.modifiers
+            && match (&self.key, &other.key) {
+                (Key::Named(name), Key::Named(other_name)) => name.eq_ignore_ascii_case(other_name),
+                (key, other_key) => key == other_key,
+            }
+    }
+
+    // Character key without Ctrl, Alt or Cmd is typed in exercises
+    pub fn is_plain_character(&self) -> bool {
+        matches!(self.key, Key::Character(_))
+            && self.modifiers.difference(Modifiers::SHIFT).is_empty()
+    }
+
+    // Keys to highlight on keyboard, modifiers first
+    pub fn keys(&self) -> Vec<Key> {
+        let mut keys: Vec<Key> = [
+            (Modifiers::CTRL, "Control"),
+            (Modifiers::SHIFT, "Shift"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::LOGO, "Super"),
+        ]
+        .iter()
+        .filter(|(modifier, _)| self.modifiers.contains(*modifier))
+        .map(|(_, name)| Key::Named(name.to_string()))
+        .collect();
+        keys.push(self.key.clone());
+        keys
+    }
+}
+
+// Exercise, where student presses given key chords one after another.
+pub struct ShortcutComponent {
+    chords: Vec<Chord>,
+    current: usize,
+    // First chord is timed from first key press, not from page shown
+    shown_at: Option<Instant>,
+    paused_at: Option<Instant>,
+    // Time from chord shown till it was pressed
+    pub times: Vec<Duration>,
+    pub errors: u64,
+    text_size: f32,
+}
+
+impl ShortcutComponent {
+    pub fn new(chords: &[String]) -> Self {
+        ShortcutComponent {
+            chords: chords
+                .iter()
+                .map(String::as_str)
+                .filter_map(Chord::parse)
+                .collect(),
+            current: 0,
+            shown_at: None,
+            paused_at: None,
+            times: vec![],
+            errors: 0,
+            text_size: scale::EXERCISE_SIZE,
+        }
+    }
+
+    // Returns sound event to play, if any
+    pub fn update(&mut self, message: Message) -> Option<SoundEvent> {
+        match message {
+            Message::Event(Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                key,
+                modifiers,
+                ..
+            })) => self.key_pressed(&key, modifiers),
+            Message::Event(_) => None,
+            Message::SetTextSize(size) => {
+                self.text_size = size;
+                None
+            }
+            Message::SetPaused(paused) => {
+                match (paused, self.paused_at) {
+                    (true, None) => self.paused_at = Some(Instant::now()),
+                    (false, Some(paused_at)) => {
+                        if let Some(shown_at) = self.shown_at.as_mut() {
+                            *shown_at += paused_at.elapsed();
+                        }
+                        self.paused_at = None;
+                    }
+                    _ => {}
+                }
+                None
+            }
+        }
+    }
+
+    fn key_pressed(
+        &mut self,
+        key: &iced::keyboard::Key,
+        modifiers: Modifiers,
+    ) -> Option<SoundEvent> {
+        let chord = self.chords.get(self.current)?;
+        let shown_at = *self.shown_at.get_or_insert_with(Instant::now);
+        // Chord is complete only when non modifier key is pressed
+        if let iced::keyboard::Key::Named(
+            key::Named::Control
+            | key::Named::Shift
+            | key::Named::Alt
+            | key::Named::AltGraph
+            | key::Named::Super,
+        ) = key
+        {
+            return None;
+        }
+        if chord.matches(key, modifiers) {
+            self.times.push(shown_at.elapsed());
+            self.current += 1;
+            self.shown_at = Some(Instant::now());
+            Some(SoundEvent::KeyPress)
+        } else {
+            self.errors += 1;
+            Some(SoundEvent::Error)
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let current = match self.current_chord() {
+            Some(chord) => chord.label.clone(),
+            None => "Done".to_string(),
+        };
+        let mut content = column![text(current)
+            .size(self.text_size * 2.0)
+            .font(font::MONO.clone())]
+        .spacing(self.text_size / 2.0)
+        .padding(self.text_size / 2.0);
+        content = content.push(
+            text(format!(
+                "{} / {} shortcuts, {} errors",
+                self.current,
+                self.chords.len(),
+                self.errors
+            ))
+            .size(self.text_size),
+        );
+        if let Some(time) = self.times.last() {
+            content = content.push(
+                text(format!("Last shortcut in {:.2} s", time.as_secs_f64())).size(self.text_size),
+            );
+        }
+        content.into()
+    }
+
+    pub fn current_chord(&self) -> Option<&Chord> {
+        self.chords.get(self.current)
+    }
+
+    pub fn finished(&self) -> bool {
+        self.current >= self.chords.len()
+    }
+
+    pub fn chords(&self) -> usize {
+        self.chords.len()
+    }
+
+    // Average time to press chord in seconds
+    pub fn chord_time(&self) -> Option<f64> {
+        if self.times.is_empty() {
+            return None;
+        }
+        let total: f64 = self.times.iter().map(Duration::as_secs_f64).sum();
+        Some(total / self.times.len() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn character(character: &str) -> iced::keyboard::Key {
+        iced::keyboard::Key::Character(character.into())
+    }
+
+    #[test]
+    fn short_key_names_are_expanded() {
+        let chord = Chord::parse("Ctrl+Esc").unwrap();
+        assert!(chord.matches(
+            &iced::keyboard::Key::Named(key::Named::Escape),
+            Modifiers::CTRL
+        ));
+        assert!(Chord::parse("Ctrl+Shift").is_none());
+    }
+
+    #[test]
+    fn plus_key_is_written_as_empty_part() {
+        let chord = Chord::parse("Ctrl++").unwrap();
+        assert!(chord.matches(&character("+"), Modifiers::CTRL));
+        assert!(!chord.matches(&character("+"), Modifiers::empty()));
+    }
+
+    #[test]
+    fn shift_of_symbol_key_is_ignored() {
+        let chord = Chord::parse("Ctrl+?").unwrap();
+        assert!(chord.matches(&character("?"), Modifiers::CTRL | Modifiers::SHIFT));
+        assert!(!chord.matches(&character("?"), Modifiers::SHIFT));
+        let letter = Chord::parse("Ctrl+T").unwrap();
+        assert!(!letter.matches(&character("T"), Modifiers::CTRL | Modifiers::SHIFT));
+    }
+
+    #[test]
+    fn mod_is_platform_command_key() {
+        let chord = Chord::parse("Mod+P").unwrap();
+        if cfg!(target_os = "macos") {
+            assert!(chord.matches(&character("p"), Modifiers::LOGO));
+            assert!(!chord.matches(&character("p"), Modifiers::CTRL));
+        } else {
+            assert!(chord.matches(&character("p"), Modifiers::CTRL));
+            assert!(!chord.matches(&character("p"), Modifiers::LOGO));
+        }
+    }
+
+    #[test]
+    fn shift_letter_matches_uppercase_character() {
+        let chord = Chord::parse("Shift+Alt+T").unwrap();
+        assert!(chord.matches(&character("T"), Modifiers::SHIFT | Modifiers::ALT));
+        assert!(!chord.matches(&character("T"), Modifiers::ALT));
+    }
+
+    #[test]
+    fn keys_list_modifiers_first() {
+        assert_eq!(
+            Chord::parse("Shift+Ctrl+T").unwrap().keys(),
+            vec![
+                Key::Named("Control".to_string()),
+                Key::Named("Shift".to_string()),
+                Key::Character("t".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn key_names_are_case_insensitive() {
+        let help = Chord::parse("f1").unwrap();
+        assert!(help.matches(
+            &iced::keyboard::Key::Named(key::Named::F1),
+            Modifiers::empty()
+        ));
+        let next = Chord::parse("ENTER").unwrap();
+        assert!(next.matches(
+            &iced::keyboard::Key::Named(key::Named::Enter),
+            Modifiers::empty()
+        ));
+        assert!(help.same_keys(&Chord::parse("F1").unwrap()));
+    }
+
+    #[test]
+    fn same_keys_ignores_case_and_modifier_order() {
+        let chord = Chord::parse("Shift+Alt+Up").unwrap();
+        assert!(chord.same_keys(&Chord::parse("alt+shift+arrowup").unwrap()));
+        assert!(!chord.same_keys(&Chord::parse("Alt+Up").unwrap()));
+    }
+
+    #[test]
+    fn characters_without_command_modifier_are_typed() {
+        assert!(Chord::parse("Shift+T").unwrap().is_plain_character());
+        assert!(!Chord::parse("Shift+Alt+T").unwrap().is_plain_character());
+        assert!(!Chord::parse("F1").unwrap().is_plain_character());
+    }
+
+    #[test]
+    fn first_chord_is_timed_from_first_key_press() {
+        let mut shortcuts = ShortcutComponent::new(&["Ctrl+C".to_string(), "F5".to_string()]);
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(shortcuts.shown_at.is_none());
+        shortcuts.key_pressed(
+            &iced::keyboard::Key::Named(key::Named::Control),
+            Modifiers::CTRL,
+        );
+        shortcuts.key_pressed(&character("c"), Modifiers::CTRL);
+        std::thread::sleep(Duration::from_millis(30));
+        shortcuts.key_pressed(
+            &iced::keyboard::Key::Named(key::Named::F5),
+            Modifiers::empty(),
+        );
+        assert!(shortcuts.finished());
+        assert!(shortcuts.times[0] < Duration::from_millis(30));
+        assert!(shortcuts.times[1] >= Duration::from_millis(30));
+    }
+}
@@ -0,0 +1,260 @@
+use std::{fs, io::Cursor, path::PathBuf, sync::Arc, time::Duration};
+
+use rodio::{Decoder, Source};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+// Events application plays sound for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SoundEvent {
+    KeyPress,
+    Error,
+    ExerciseComplete,
+    LessonComplete,
+    NewRecord,
+    // Metronome beat of rhythm exercises, played regardless of event settings
+    Metronome,
+}
+
+impl SoundEvent {
+    // Events, which can be switched on/off in settings
+    pub const ALL: [SoundEvent; 5] = [
+        SoundEvent::KeyPress,
+        SoundEvent::Error,
+        SoundEvent::ExerciseComplete,
+        SoundEvent::LessonComplete,
+        SoundEvent::NewRecord,
+    ];
+
+    // File name without extension in user sound pack folder
+    pub fn file_stem(&self) -> &'static str {
+        match self {
+            SoundEvent::KeyPress => "keypress",
+            SoundEvent::Error => "error",
+            SoundEvent::ExerciseComplete => "exercise_complete",
+            SoundEvent::LessonComplete => "lesson_complete",
+            SoundEvent::NewRecord => "new_record",
+            SoundEvent::Metronome => "metronome",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SoundEvent::KeyPress => "Key press",
+            SoundEvent::Error => "Typing error",
+            SoundEvent::ExerciseComplete => "Exercise complete",
+            SoundEvent::LessonComplete => "Lesson complete",
+            SoundEvent::NewRecord => "New record",
+            SoundEvent::Metronome => "Metronome",
+        }
+    }
+}
+
+pub const CLASSIC_PACK: &str = "classic";
+pub const TONES_PACK: &str = "tones";
+const SOUND_EXTENSIONS: [&str; 3] = ["wav", "ogg", "mp3"];
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct SoundSettings {
+    #[serde(default = "default_pack")]
+    pub pack: String,
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    #[serde(default)]
+    pub mute: bool,
+    // Events which sound is played for
+    #[serde(default = "default_events")]
+    pub events: Vec<SoundEvent>,
+}
+
+fn default_pack() -> String {
+    CLASSIC_PACK.to_string()
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+fn default_events() -> Vec<SoundEvent> {
+    vec![
+        SoundEvent::Error,
+        SoundEvent::ExerciseComplete,
+        SoundEvent::LessonComplete,
+        SoundEvent::NewRecord,
+    ]
+}
+
+impl SoundSettings {
+    // Volume sounds are played with, zero when muted
+    pub fn output_volume(&self) -> f32 {
+        if self.mute {
+            0.0
+        } else {
+            self.volume.clamp(0.0, 1.0)
+        }
+    }
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        SoundSettings {
+            pack: default_pack(),
+            volume: default_volume(),
+            mute: false,
+            events: default_events(),
+        }
+    }
+}
+
+// Decoded sound samples. Samples are shared, so clip can be
+// played many times without decoding it again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clip {
+    samples: Arc<Vec<i16>>,
+    channels: u16,
+    sample_rate: u32,
+    position: usize,
+}
+
+impl Clip {
+    // Decodes WAV/OGG/MP3 data
+    fn decode(data: Vec<u8>) -> Option<Clip> {
+        let decoder = Decoder::new(Cursor::new(data)).ok()?;
+        let channels = decoder.channels();
+        let sample_rate = decoder.sample_rate();
+        Some(Clip {
+            samples: Arc::new(decoder.collect()),
+            channels,
+            sample_rate,
+            position: 0,
+        })
+    }
+}
+
+impl Iterator for Clip {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        let sample = self.samples.get(self.position).copied();
+        self.position += 1;
+        sample
+    }
+}
+
+impl Source for Clip {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.samples.len().saturating_sub(self.position))
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sound {
+    Clip(Clip),
+    // Generated sine tone
+    Tone { frequency: f32, millis: u64 },
+    None,
+}
+
+// Sounds for all events. Volume is not part of theme, so changing
+// it does not require decoding sounds again.
+// Default theme has no sounds.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SoundTheme {
+    sounds: Vec<(SoundEvent, Sound)>,
+}
+
+impl SoundTheme {
+    pub fn load(settings: &SoundSettings) -> Self {
+        let builtin = builtin_sounds(&settings.pack);
+        let user_pack = Self::user_packs_dir().join(&settings.pack);
+        let sounds = SoundEvent::ALL
+            .iter()
+            .chain([SoundEvent::Metronome].iter())
+            .map(|event| {
+                let sound = if *event != SoundEvent::Metronome && !settings.events.contains(event) {
+                    Sound::None
+                } else {
+                    // Sounds missing in user pack are taken from classic pack
+                    load_user_sound(&user_pack, *event).unwrap_or_else(|| {
+                        builtin
+                            .iter()
+                            .find(|(builtin_event, _)| builtin_event == event)
+                            .map(|(_, sound)| sound.clone())
+                            .unwrap_or(Sound::None)
+                    })
+                };
+                (*event, sound)
+            })
+            .collect();
+
+        SoundTheme { sounds }
+    }
+
+    pub fn sound(&self, event: SoundEvent) -> Sound {
+        self.sounds
+            .iter()
+            .find(|(sound_event, _)| *sound_event == event)
+            .map(|(_, sound)| sound.clone())
+            .unwrap_or(Sound::None)
+    }
+
+    // User sound packs are folders in sounds folder of config directory.
+    // Each pack contains files named by event, e.g. error.wav, keypress.ogg.
+    pub fn user_packs_dir() -> PathBuf {
+        Config::config_dir().join("sounds")
+    }
+
+    pub fn packs() -> Vec<String> {
+        let mut packs = vec![CLASSIC_PACK.to_string(), TONES_PACK.to_string()];
+        if let Ok(entries) = fs::read_dir(Self::user_packs_dir()) {
+            let mut user_packs: Vec<String> = entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().to_str().map(String::from))
+                .filter(|name| !packs.contains(name))
+                .collect();
+            user_packs.sort();
+            packs.extend(user_packs);
+        }
+        packs
+    }
+}
+
+fn builtin_sounds(pack: &str) -> Vec<(SoundEvent, Sound)> {
+    let tone = |frequency, millis| Sound::Tone { frequency, millis };
+    let error = if pack == TONES_PACK {
+        tone(220.0, 150)
+    } else {
+        Clip::decode(include_bytes!("../sounds/clack.mp3").to_vec())
+            .map(Sound::Clip)
+            .unwrap_or(Sound::None)
+    };
+    vec![
+        (SoundEvent::KeyPress, tone(1200.0, 15)),
+        (SoundEvent::Error, error),
+        (SoundEvent::ExerciseComplete, tone(880.0, 120)),
+        (SoundEvent::LessonComplete, tone(660.0, 300)),
+        (SoundEvent::NewRecord, tone(1320.0, 250)),
+        (SoundEvent::Metronome, tone(1000.0, 20)),
+    ]
+}
+
+fn load_user_sound(pack_dir: &std::path::Path, event: SoundEvent) -> Option<Sound> {
+    SOUND_EXTENSIONS.iter().find_map(|ext| {
+        let path = pack_dir.join(format!("{}.{}", event.file_stem(), ext));
+        fs::read(path).ok().and_then(Clip::decode).map(Sound::Clip)
+    })
+}
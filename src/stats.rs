@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{exercise_component::ExerciseComponent, shortcut_component::ShortcutComponent};
+
+// Results of page exercises, available to page templates
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct Stats {
+    // Net words per minute (errors excluded)
+    pub wpm: f64,
+    pub gross_wpm: f64,
+    pub errors: u64,
+    // Correctly typed characters in percents
+    pub accuracy: f64,
+    // Errors in percents of exercise characters
+    pub error_rate: f64,
+    // Exercise duration in seconds: sum of times spent typing each line
+    pub duration: f64,
+    // Rhythm consistency in percents for metronome exercises
+    pub rhythm: Option<f64>,
+    // Errors by expected symbol (brackets, operators and other
+    // non alphanumeric characters)
+    pub symbol_errors: BTreeMap<String, u64>,
+    // Average seconds to press shortcut in shortcut exercises
+    pub chord_time: Option<f64>,
+}
+
+impl Stats {
+    pub fn new(exercise_components: &[ExerciseComponent], rhythm: Option<f64>) -> Self {
+        let mut errors: u64 = 0;
+        let mut mseconds: u64 = 0;
+        let mut length: u64 = 0;
+        let mut symbol_errors: BTreeMap<String, u64> = BTreeMap::new();
+        for ex in exercise_components {
+            errors += ex.errors;
+            mseconds += ex.mseconds;
+            length += ex
+                .exercise
+                .chars()
+                .skip(ex.prefilled)
+                .map(|_| 1)
+                .sum::<u64>();
+            for (character, count) in &ex.char_errors {
+                if !character.is_alphanumeric() && !character.is_whitespace() {
+                    *symbol_errors.entry(character.to_string()).or_default() += count;
+                }
+            }
+        }
+        let minutes = mseconds as f64 / 60000.0;
+        let correct = length.saturating_sub(errors) as f64;
+        let (wpm, gross_wpm) = if minutes > 0.0 {
+            (correct / minutes / 5.0, length as f64 / minutes / 5.0)
+        } else {
+            (0.0, 0.0)
+        };
+        let (accuracy, error_rate) = if length > 0 {
+            (
+                correct / length as f64 * 100.0,
+                errors as f64 / length as f64 * 100.0,
+            )
+        } else {
+            (100.0, 0.0)
+        };
+        Stats {
+            wpm: round(wpm),
+            gross_wpm: round(gross_wpm),
+            errors,
+            accuracy: round(accuracy),
+            error_rate: round(error_rate),
+            duration: round(mseconds as f64 / 1000.0),
+            rhythm,
+            symbol_errors,
+            chord_time: None,
+        }
+    }
+
+    // Speed of shortcut exercise is shortcuts per minute, so page gates
+    // and records work same way as for typing exercises
+    pub fn from_shortcuts(shortcuts: &ShortcutComponent) -> Self {
+        let chords = shortcuts.chords() as u64;
+        let attempts = chords + shortcuts.errors;
+        let duration: f64 = shortcuts.times.iter().map(|time| time.as_secs_f64()).sum();
+        let minutes = duration / 60.0;
+        let (wpm, gross_wpm) = if minutes > 0.0 {
+            (chords as f64 / minutes, attempts as f64 / minutes)
+        } else {
+            (0.0, 0.0)
+        };
+        Stats {
+            wpm: round(wpm),
+            gross_wpm: round(gross_wpm),
+            errors: shortcuts.errors,
+            accuracy: if attempts > 0 {
+                round(chords as f64 / attempts as f64 * 100.0)
+            } else {
+                100.0
+            },
+            error_rate: if chords > 0 {
+                round(shortcuts.errors as f64 / chords as f64 * 100.0)
+            } else {
+                0.0
+            },
+            duration: round(duration),
+            chord_time: shortcuts.chord_time().map(round),
+            ..Default::default()
+        }
+    }
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn line(exercise: &str, errors: u64, mseconds: u64) -> ExerciseComponent {
+        let mut ex = ExerciseComponent::new(exercise);
+        ex.errors = errors;
+        ex.mseconds = mseconds;
+        ex
+    }
+
+    #[test]
+    fn times_and_characters_of_lines_are_summed() {
+        let lines = [line("asdfg hjkl", 1, 7000), line("qwert yuio", 1, 5000)];
+        let stats = Stats::new(&lines, None);
+        // 20 characters, 18 of them correct, in 12 seconds
+        assert_eq!(stats.duration, 12.0);
+        assert_eq!(stats.errors, 2);
+        assert_eq!(stats.gross_wpm, 20.0);
+        assert_eq!(stats.wpm, 18.0);
+        assert_eq!(stats.accuracy, 90.0);
+        assert_eq!(stats.error_rate, 10.0);
+    }
+
+    #[test]
+    fn prefilled_characters_are_not_counted() {
+        let mut indented = line("    fn main", 0, 3000);
+        indented.prefilled = 4;
+        let stats = Stats::new(&[indented, line("}", 0, 0)], None);
+        // 8 typed characters in 3 seconds
+        assert_eq!(stats.gross_wpm, 32.0);
+        assert_eq!(stats.duration, 3.0);
+    }
+
+    #[test]
+    fn shortcut_speed_is_shortcuts_per_minute() {
+        let mut shortcuts = ShortcutComponent::new(&["Mod+C".to_string(), "Mod+V".to_string()]);
+        shortcuts.times = vec![Duration::from_secs(2), Duration::from_secs(4)];
+        shortcuts.errors = 1;
+        let stats = Stats::from_shortcuts(&shortcuts);
+        // 2 shortcuts and 1 error in 6 seconds
+        assert_eq!(stats.wpm, 20.0);
+        assert_eq!(stats.gross_wpm, 30.0);
+        assert_eq!(stats.duration, 6.0);
+        assert_eq!(stats.chord_time, Some(3.0));
+    }
+}
@@ -0,0 +1,147 @@
+use handlebars::{Context, Handlebars, Helper, HelperResult, Output, RenderContext, Renderable};
+use serde::Serialize;
+
+use crate::{
+    config::{Keybindings, Lesson},
+    stats::Stats,
+};
+
+// Data available to lesson page templates (title, content, content2 and fail_content)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PageContext {
+    // Results of previous exercise: wpm, gross_wpm, errors, accuracy, duration, rhythm
+    #[serde(flatten)]
+    pub stats: Stats,
+    pub net_wpm: f64,
+    // Best result ever reached for previous exercise
+    pub best_wpm: Option<f64>,
+    // Current page number, starting with 1
+    pub page: usize,
+    pub pages: usize,
+    pub lesson: String,
+    pub student: String,
+    pub keyboard: String,
+    // Pass thresholds of current page
+    pub min_wpm: Option<f64>,
+    pub max_error_rate: Option<f64>,
+    // Configured chords, e.g. {{keys.next}}, so hints follow rebound keys
+    pub keys: Keybindings,
+}
+
+// Lesson page templates compiled once on lesson load.
+pub struct PageRenderer {
+    registry: Handlebars<'static>,
+}
+
+impl Default for PageRenderer {
+    fn default() -> Self {
+        let mut registry = Handlebars::new();
+        // Page content is plain text, so <Enter> should stay as is
+        registry.register_escape_fn(handlebars::no_escape);
+        registry.register_helper("pass", Box::new(pass_helper));
+        registry.register_helper("fail", Box::new(fail_helper));
+        PageRenderer { registry }
+    }
+}
+
+impl PageRenderer {
+    pub fn set_lesson(&mut self, lesson: Option<&Lesson>) {
+        self.registry.clear_templates();
+        let Some(lesson) = lesson else {
+            return;
+        };
+        for (index, page) in lesson.pages.iter().enumerate() {
+            for (field, template) in [
+                ("title", &page.title),
+                ("content", &page.content),
+                ("content2", &page.content2),
+                ("fail_content", &page.fail_content),
+            ] {
+                if let Err(err) = self
+                    .registry
+                    .register_template_string(&Self::name(index, field), template)
+                {
+                    println!("page {} {} template error: {}", index + 1, field, err);
+                }
+            }
+            for (block_index, block) in page.blocks.iter().enumerate() {
+                for (text_index, template) in block.texts().into_iter().enumerate() {
+                    let field = format!("block{}.{}", block_index, text_index);
+                    if let Err(err) = self
+                        .registry
+                        .register_template_string(&Self::name(index, &field), template)
+                    {
+                        println!("page {} {} template error: {}", index + 1, field, err);
+                    }
+                }
+            }
+        }
+    }
+
+    // Renders page field. If template fails, text is shown as is.
+    pub fn render(&self, page: usize, field: &str, raw: &str, context: &PageContext) -> String {
+        self.registry
+            .render(&Self::name(page, field), context)
+            .unwrap_or_else(|_| raw.to_string())
+    }
+
+    fn name(page: usize, field: &str) -> String {
+        format!("{}.{}", page, field)
+    }
+}
+
+// {{#pass wpm=20 accuracy=95}}...{{else}}...{{/pass}}
+// Renders block if previous exercise reached all given limits.
+// Supported limits are wpm, gross_wpm, accuracy (minimums), errors and error_rate (maximums).
+fn pass_helper<'reg, 'rc>(
+    h: &Helper<'rc>,
+    r: &'reg Handlebars<'reg>,
+    ctx: &'rc Context,
+    rc: &mut RenderContext<'reg, 'rc>,
+    out: &mut dyn Output,
+) -> HelperResult {
+    render_conditional(limits_met(h, ctx), h, r, ctx, rc, out)
+}
+
+// {{#fail wpm=20}}...{{/fail}} - opposite of pass
+fn fail_helper<'reg, 'rc>(
+    h: &Helper<'rc>,
+    r: &'reg Handlebars<'reg>,
+    ctx: &'rc Context,
+    rc: &mut RenderContext<'reg, 'rc>,
+    out: &mut dyn Output,
+) -> HelperResult {
+    render_conditional(!limits_met(h, ctx), h, r, ctx, rc, out)
+}
+
+fn limits_met(h: &Helper, ctx: &Context) -> bool {
+    let data = ctx.data();
+    h.hash().iter().all(|(name, limit)| {
+        let Some(limit) = limit.value().as_f64() else {
+            return true;
+        };
+        let Some(value) = data.get(*name).and_then(|value| value.as_f64()) else {
+            return false;
+        };
+        if *name == "errors" || *name == "error_rate" {
+            value <= limit
+        } else {
+            value >= limit
+        }
+    })
+}
+
+fn render_conditional<'reg, 'rc>(
+    condition: bool,
+    h: &Helper<'rc>,
+    r: &'reg Handlebars<'reg>,
+    ctx: &'rc Context,
+    rc: &mut RenderContext<'reg, 'rc>,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let template = if condition { h.template() } else { h.inverse() };
+    match template {
+        Some(template) => template.render(r, ctx, rc, out),
+        None => Ok(()),
+    }
+}